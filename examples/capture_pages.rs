@@ -0,0 +1,83 @@
+//! Save live Nyaa.si pages for the cases tested in tests/synthetic_pages.rs
+//! (empty search, last page, remakes, trusted uploads and missing magnets)
+//! under tests/fixtures/captured, where tests/captured_pages.rs picks them up.
+//!
+//! cargo run --example capture_pages [base url] [output directory]
+
+extern crate nyaadesu;
+extern crate reqwest;
+
+use nyaadesu::*;
+
+// How many pages of the front page to look through for a remake or a row
+// without a magnet link.
+const PAGES_TO_SCAN: u32 = 10;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let base_url = args.next().unwrap_or_else(|| "https://nyaa.si".to_string());
+    let output = args
+        .next()
+        .unwrap_or_else(|| "tests/fixtures/captured".to_string());
+    std::fs::create_dir_all(&output).expect("could not create the output directory");
+
+    let client = reqwest::blocking::Client::new();
+    let get = |query: &str| -> String {
+        let url = format!("{}/?{}", base_url, query);
+        println!("Fetching {}", url);
+        client
+            .get(&url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.text())
+            .expect("could not fetch the page")
+    };
+    let save = |case: &str, html: &str| {
+        let path = format!("{}/{}.html", output, case);
+        std::fs::write(&path, html).expect("could not save the page");
+        println!("Saved {}", path);
+    };
+
+    save("empty_search", &get("q=nyaadesu+capture+without+results"));
+
+    let first = get("q=little+witch+academia");
+    let last_page = parse_search_page(&first)
+        .ok()
+        .and_then(|page| page.meta)
+        .map(|meta| meta.last_page)
+        .unwrap_or(1);
+    save(
+        "last_page",
+        &get(&format!("q=little+witch+academia&p={}", last_page)),
+    );
+
+    save("trusted", &get("f=2"));
+
+    let mut remakes = None;
+    let mut missing_magnet = None;
+    for p in 1..=PAGES_TO_SCAN {
+        let html = get(&format!("p={}", p));
+        let torrents = match parse_search_page(&html) {
+            Ok(page) => page.torrents,
+            Err(e) => {
+                eprintln!("Could not parse page {}: {}", p, e);
+                continue;
+            }
+        };
+        if remakes.is_none() && torrents.iter().any(|t| t.status == Status::Remake) {
+            remakes = Some(html.clone());
+        }
+        if missing_magnet.is_none() && torrents.iter().any(|t| t.links.magnet.is_none()) {
+            missing_magnet = Some(html);
+        }
+        if remakes.is_some() && missing_magnet.is_some() {
+            break;
+        }
+    }
+    for (case, html) in [("remakes", remakes), ("missing_magnet", missing_magnet)].iter() {
+        match html {
+            Some(html) => save(case, html),
+            None => eprintln!("No page with {} in the first {} pages", case, PAGES_TO_SCAN),
+        }
+    }
+}
@@ -22,7 +22,7 @@ fn print_torrents(ts: Vec<nyaadesu::Torrent>) {
 
 fn handle_error(e: nyaadesu::Error) {
     match e {
-        nyaadesu::Error::Request => println!("There was a problem contacting Nyaa.si"),
-        nyaadesu::Error::Scraping => panic!("There was a scraping problem. Please report this bug at https//github.com/gRastello/nyaadesu"),
+        nyaadesu::Error::Scraping(e) => panic!("There was a scraping problem ({}). Please report this bug at https//github.com/gRastello/nyaadesu", e),
+        e => println!("There was a problem contacting Nyaa.si: {}", e),
     }
 }
@@ -20,8 +20,7 @@ fn print_torrents(ts: Vec<nyaadesu::Torrent>) {
         print!("Name                                     ");
         print!("Link ");
         print!("Date             ");
-        print!("Seeders/Leechers/Completed");
-        print!("\n");
+        println!("Seeders/Leechers/Completed");
 
         for t in ts.iter().take(20) {
             // Print torrent's name.
@@ -43,20 +42,20 @@ fn print_torrents(ts: Vec<nyaadesu::Torrent>) {
             }
 
             // Print torrent's date.
-            print!("{} ", t.date);
+            print!("{:<16} ", t.date.relative());
 
             // Print seeders/leechers/completed
             print!("{}/{}/{}", t.seeders, t.leechers, t.completed_downloads);
 
             // Final newline.
-            print!("\n");
+            println!();
         }
     }
 }
 
 fn handle_error(e: nyaadesu::Error) {
     match e {
-        nyaadesu::Error::Request => println!("There was a problem contacting Nyaa.si"),
-        nyaadesu::Error::Scraping => panic!("There was a scraping problem. Please report this bug at https//github.com/gRastello/nyaadesu"),
+        nyaadesu::Error::Scraping(e) => panic!("There was a scraping problem ({}). Please report this bug at https//github.com/gRastello/nyaadesu", e),
+        e => println!("There was a problem contacting Nyaa.si: {}", e),
     }
 }
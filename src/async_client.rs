@@ -0,0 +1,285 @@
+extern crate futures_util;
+extern crate reqwest;
+extern crate tokio;
+
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+
+use crate::builder::*;
+use crate::check::*;
+use crate::details::*;
+use crate::metainfo::*;
+use crate::query::*;
+use crate::retry::*;
+use crate::rss::*;
+use crate::scraping::*;
+use crate::site::*;
+use crate::types::*;
+
+/// Asynchronous version of [`NyaaClient`], to be used on a [tokio] runtime.
+///
+/// It is available with the `async` feature.
+///
+/// ```no_run
+/// # async fn run() -> Result<(), nyaadesu::Error> {
+/// let client = nyaadesu::NyaaClient::builder().build_async()?;
+/// let torrents = client.torrent_search("madoka").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`NyaaClient`]: ./struct.NyaaClient.html
+/// [tokio]: https://tokio.rs
+#[derive(Debug, Clone)]
+pub struct AsyncNyaaClient {
+    pub(crate) base_url: String,
+    pub(crate) site: Site,
+    pub(crate) parse_mode: ParseMode,
+    pub(crate) client: reqwest::Client,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+}
+
+impl AsyncNyaaClient {
+    /// Create a client for Nyaa.si with the default configuration.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the underlying HTTP client can't be initialized (e.g. if
+    /// no TLS backend is available). Use [`AsyncNyaaClient::builder`] to
+    /// handle the error instead.
+    ///
+    /// [`AsyncNyaaClient::builder`]: #method.builder
+    pub fn new() -> AsyncNyaaClient {
+        AsyncNyaaClient::builder()
+            .build_async()
+            .expect("could not initialize the HTTP client")
+    }
+
+    /// Create a builder to configure a client.
+    pub fn builder() -> NyaaClientBuilder {
+        NyaaClientBuilder::new()
+    }
+
+    /// Url of the site the client talks to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Site the client talks to (or to a mirror of).
+    pub fn site(&self) -> Site {
+        self.site
+    }
+
+    /// Search for torrents by name.
+    pub async fn torrent_search(&self, s: &str) -> Result<Vec<Torrent>, Error> {
+        self.search(&SearchQuery::new(s)).await
+    }
+
+    /// Search for torrents matching a [`SearchQuery`], fetching every page of
+    /// results (or as many as the query's limits allow).
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    pub async fn search(&self, query: &SearchQuery) -> Result<Vec<Torrent>, Error> {
+        self.search_results(query).try_collect().await
+    }
+
+    /// Stream the pages of results of a [`SearchQuery`], fetching them only
+    /// when they are needed.
+    ///
+    /// The stream stops after the last page of results, after the query's
+    /// [`max_pages`] or after the first error.
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    /// [`max_pages`]: ./struct.SearchQuery.html#method.max_pages
+    pub fn search_pages<'a>(
+        &'a self,
+        query: &SearchQuery,
+    ) -> impl Stream<Item = Result<SearchPage, Error>> + 'a {
+        let query = query.clone();
+
+        stream::unfold(Some(1), move |next| {
+            let query = query.clone();
+            async move {
+                let n = next?;
+                if let Some(max_pages) = query.max_pages {
+                    if n > max_pages {
+                        return None;
+                    }
+                }
+
+                match self.search_page(&query, n).await {
+                    Ok(page) if page.state != Page::Torrents => None,
+                    Ok(page) => {
+                        // Pages that tell how many pages there are spare the
+                        // request of an empty page after the last one.
+                        let last = page.meta.is_some_and(|m| m.is_last_page());
+                        let next = if last { None } else { Some(n + 1) };
+                        Some((Ok(page), next))
+                    }
+                    // Asking for a page past the last one may be answered with
+                    // a 404.
+                    Err(Error::Status(404)) if n > 1 => None,
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        })
+    }
+
+    /// Stream the torrents matching a [`SearchQuery`], fetching pages of
+    /// results only when they are needed.
+    ///
+    /// The stream stops after the last torrent, after the query's [`limit`]
+    /// or after the first error.
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    /// [`limit`]: ./struct.SearchQuery.html#method.limit
+    pub fn search_results<'a>(
+        &'a self,
+        query: &SearchQuery,
+    ) -> impl Stream<Item = Result<Torrent, Error>> + 'a {
+        let limit = query.limit.unwrap_or(usize::MAX);
+
+        self.search_pages(query)
+            .map_ok(|page| stream::iter(page.torrents.into_iter().map(Ok)))
+            .try_flatten()
+            .take(limit)
+    }
+
+    /// Get the n-th page of results (starting from 1) of a [`SearchQuery`].
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    pub async fn search_page(&self, query: &SearchQuery, n: u32) -> Result<SearchPage, Error> {
+        let page = self
+            .send(
+                self.client
+                    .get(format!("{}/", self.base_url))
+                    .query(&query.params(n)),
+            )
+            .await?
+            .bytes()
+            .await?;
+
+        let mut search_page = read_search_page_with(&mut &page[..], self.parse_mode)?;
+        for torrent in search_page.torrents.iter_mut() {
+            torrent.resolve_links(&self.base_url);
+        }
+
+        Ok(search_page)
+    }
+
+    /// Search for torrents matching a [`SearchQuery`] using the RSS feed
+    /// instead of the html pages (see [`NyaaClient::search_rss`]).
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    /// [`NyaaClient::search_rss`]: ./struct.NyaaClient.html#method.search_rss
+    pub async fn search_rss(&self, query: &SearchQuery) -> Result<Vec<Torrent>, Error> {
+        let feed = self
+            .send(
+                self.client
+                    .get(format!("{}/", self.base_url))
+                    .query(&query.rss_params()),
+            )
+            .await?
+            .bytes()
+            .await?;
+
+        read_rss_from(&mut &feed[..], self.site)
+    }
+
+    /// Get all the information available about the torrent with the given id.
+    pub async fn torrent_details(&self, id: u32) -> Result<TorrentDetails, Error> {
+        let page = self
+            .send(self.client.get(format!("{}/view/{}", self.base_url, id)))
+            .await?
+            .bytes()
+            .await?;
+
+        let mut details = read_view_page(&mut &page[..])?;
+        details.torrent.resolve_links(&self.base_url);
+
+        Ok(details)
+    }
+
+    /// Download the .torrent file of the torrent with the given id.
+    pub async fn download_torrent(&self, id: u32) -> Result<Vec<u8>, Error> {
+        let file = self
+            .send(
+                self.client
+                    .get(format!("{}/download/{}.torrent", self.base_url, id)),
+            )
+            .await?
+            .bytes()
+            .await?;
+
+        Ok(file.to_vec())
+    }
+
+    /// Download and decode the .torrent file of the torrent with the given
+    /// id.
+    pub async fn torrent_metainfo(&self, id: u32) -> Result<Metainfo, Error> {
+        parse_torrent_file(&self.download_torrent(id).await?)
+    }
+
+    /// Check that the site's pages can still be scraped (see
+    /// [`NyaaClient::self_check`]).
+    ///
+    /// [`NyaaClient::self_check`]: ./struct.NyaaClient.html#method.self_check
+    pub async fn self_check(&self) -> Result<SelfCheck, Error> {
+        let page = self
+            .send(self.client.get(format!("{}/", self.base_url)))
+            .await?
+            .bytes()
+            .await?;
+
+        let mut check = check_search_page(&String::from_utf8_lossy(&page));
+        if let Some(id) = first_torrent_id(&page) {
+            let view = self
+                .send(self.client.get(format!("{}/view/{}", self.base_url, id)))
+                .await?
+                .text()
+                .await?;
+            check.fields.extend(check_view_page(&view).fields);
+        }
+
+        Ok(check)
+    }
+
+    // Send a request, waiting for the rate limit and trying it again as the
+    // retry policy says. Error statuses are errors.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+        let mut retries = 0;
+        loop {
+            if let Some(limiter) = &self.rate_limiter {
+                tokio::time::sleep(limiter.reserve()).await;
+            }
+
+            let request = request
+                .try_clone()
+                // Only requests with a streamed body can't be cloned.
+                .ok_or_else(|| {
+                    Error::Configuration(
+                        "requests with a streamed body can't be retried".to_string(),
+                    )
+                })?;
+            let (error, retry_after) = match request.send().await {
+                Ok(response) => match response.error_for_status_ref() {
+                    Ok(_) => return Ok(response),
+                    Err(e) => (Error::from(e), retry_after(response.headers())),
+                },
+                Err(e) => (Error::from(e), None),
+            };
+
+            match self.retry.delay(retries, &error, retry_after) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(error),
+            }
+            retries += 1;
+        }
+    }
+}
+
+impl Default for AsyncNyaaClient {
+    fn default() -> AsyncNyaaClient {
+        AsyncNyaaClient::new()
+    }
+}
@@ -0,0 +1,123 @@
+//! Torznab server in front of Nyaa.si, for Sonarr, Radarr, Prowlarr and the
+//! like. Add it as a Torznab indexer with the url it prints (e.g.
+//! `http://127.0.0.1:9117`) and any api key (or the one given with
+//! `--api-key`).
+
+extern crate nyaadesu;
+extern crate percent_encoding;
+extern crate tiny_http;
+extern crate xml;
+
+mod torznab;
+
+use std::process;
+use std::sync::Arc;
+use std::thread;
+
+use nyaadesu::*;
+
+use crate::torznab::Config;
+
+const USAGE: &str = "\
+Usage: nyaadesu-server [options]
+
+Options:
+  --listen ADDRESS   Address to listen on (default: 127.0.0.1:9117)
+  --site SITE        nyaa (default) or sukebei
+  --base-url URL     Url of the site (e.g. a mirror)
+  --api-key KEY      Only answer requests with this api key
+  --threads N        Number of requests answered at once (default: 4)
+  -h, --help         Print this help
+";
+
+fn main() {
+    let mut listen = "127.0.0.1:9117".to_string();
+    let mut site = Site::Nyaa;
+    let mut base_url = None;
+    let mut api_key = None;
+    let mut threads = 4;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            print!("{}", USAGE);
+            return;
+        }
+
+        let value = match args.next() {
+            Some(value) => value,
+            None => usage_error(&format!("{} needs a value", arg)),
+        };
+        match arg.as_str() {
+            "--listen" => listen = value,
+            "--site" => site = value.parse().unwrap_or_else(|e: String| usage_error(&e)),
+            "--base-url" => base_url = Some(value),
+            "--api-key" => api_key = Some(value),
+            "--threads" => {
+                threads = match value.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => usage_error(&format!("invalid number of threads {:?}", value)),
+                }
+            }
+            _ => usage_error(&format!("unknown option {}", arg)),
+        }
+    }
+
+    let mut builder = NyaaClient::builder().site(site);
+    if let Some(base_url) = &base_url {
+        builder = builder.base_url(base_url);
+    }
+    let client = builder.build().unwrap_or_else(|e| fail(&e.to_string()));
+
+    let server = tiny_http::Server::http(&listen)
+        .unwrap_or_else(|e| fail(&format!("could not listen on {}: {}", listen, e)));
+    // The actual address, in case port 0 was asked for.
+    match server.server_addr().to_ip() {
+        Some(address) => println!("Listening on http://{}", address),
+        None => println!("Listening on {}", listen),
+    }
+
+    let server = Arc::new(server);
+    let config = Arc::new(Config {
+        client,
+        site,
+        api_key,
+    });
+    let workers = (0..threads)
+        .map(|_| {
+            let server = server.clone();
+            let config = config.clone();
+            thread::spawn(move || serve(&server, &config))
+        })
+        .collect::<Vec<_>>();
+
+    for worker in workers {
+        let _ = worker.join();
+    }
+}
+
+// Answer requests until the server is closed.
+fn serve(server: &tiny_http::Server, config: &Config) {
+    for request in server.incoming_requests() {
+        let answer = torznab::handle(config, request.url());
+        let content_type =
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], answer.content_type.as_bytes())
+                .expect("content types are valid headers");
+        let response = tiny_http::Response::from_data(answer.body)
+            .with_status_code(answer.status)
+            .with_header(content_type);
+
+        // The client may be gone already: there is nobody left to tell.
+        let _ = request.respond(response);
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("nyaadesu-server: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("nyaadesu-server: {}", message);
+    process::exit(1);
+}
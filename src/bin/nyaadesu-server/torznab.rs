@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+
+use nyaadesu::*;
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+const TORZNAB_NS: &str = "http://torznab.com/schemas/2015/feed";
+
+// Number of results returned when the request sets no limit, and the most
+// that can be asked for.
+const DEFAULT_LIMIT: usize = 75;
+const MAX_LIMIT: usize = 300;
+
+// Torznab's categories, with their parents' ids and their names.
+const TORZNAB_CATEGORIES: [(u32, Option<u32>, &str); 11] = [
+    (3000, None, "Audio"),
+    (3010, Some(3000), "Audio/MP3"),
+    (3040, Some(3000), "Audio/Lossless"),
+    (4000, None, "PC"),
+    (4050, Some(4000), "PC/Games"),
+    (5000, None, "TV"),
+    (5070, Some(5000), "TV/Anime"),
+    (6000, None, "XXX"),
+    (7000, None, "Books"),
+    (7030, Some(7000), "Books/Comics"),
+    (8000, None, "Other"),
+];
+
+/// Struct that represents what the server needs to answer requests.
+pub struct Config {
+    pub client: NyaaClient,
+    pub site: Site,
+    /// Key that requests must carry in their `apikey` parameter, if any.
+    pub api_key: Option<String>,
+}
+
+/// Struct that represents an answer to a request.
+pub struct Answer {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+/// Answer a request to the server (e.g. "/api?t=search&q=madoka").
+pub fn handle(config: &Config, url: &str) -> Answer {
+    let (path, query) = match url.find('?') {
+        Some(i) => (&url[..i], &url[i + 1..]),
+        None => (url, ""),
+    };
+    if path != "/api" && path != "/api/" {
+        return Answer {
+            status: 404,
+            content_type: "text/plain; charset=utf-8",
+            body: b"not found".to_vec(),
+        };
+    }
+
+    let params = query_params(query);
+    if let Some(key) = &config.api_key {
+        if params.get("apikey") != Some(key) {
+            return error(100, "Incorrect user credentials");
+        }
+    }
+
+    let result = match params.get("t").map(String::as_str) {
+        Some("caps") => Ok(caps(config.site)),
+        Some("search") | Some("tvsearch") => search(config, &params),
+        Some(t) => Err((202, format!("No such function ({})", t))),
+        None => Err((200, "Missing parameter (t)".to_string())),
+    };
+
+    match result {
+        Ok(body) => Answer {
+            status: 200,
+            content_type: "application/xml; charset=utf-8",
+            body,
+        },
+        Err((code, description)) => error(code, &description),
+    }
+}
+
+// Search Nyaa.si and render the results as a Torznab feed.
+fn search(config: &Config, params: &HashMap<String, String>) -> Result<Vec<u8>, (u16, String)> {
+    let number = |name: &str| -> Result<Option<usize>, (u16, String)> {
+        match params.get(name).filter(|v| !v.is_empty()) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| (201, format!("Incorrect parameter ({})", name))),
+            None => Ok(None),
+        }
+    };
+
+    let mut terms = params.get("q").cloned().unwrap_or_default();
+    if params.get("t").map(String::as_str) == Some("tvsearch") {
+        // Anime releases are numbered by episode (e.g. "Madoka - 03") so the
+        // episode alone is searched for unless a season is given too.
+        match (number("season")?, number("ep")?) {
+            (Some(season), Some(ep)) => terms.push_str(&format!(" S{:02}E{:02}", season, ep)),
+            (Some(season), None) => terms.push_str(&format!(" S{:02}", season)),
+            (None, Some(ep)) => terms.push_str(&format!(" {:02}", ep)),
+            (None, None) => (),
+        }
+    }
+
+    let limit = number("limit")?.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let offset = number("offset")?.unwrap_or(0);
+
+    let wanted = match params.get("cat").filter(|c| !c.is_empty()) {
+        Some(cat) => {
+            let ids = cat
+                .split(',')
+                .map(|id| id.trim().parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| (201, "Incorrect parameter (cat)".to_string()))?;
+            Some(
+                config
+                    .site
+                    .categories()
+                    .filter(|c| ids.iter().any(|id| matches(*id, *c)))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        None => None,
+    };
+
+    let mut torrents = vec![];
+    match &wanted {
+        // None of the categories asked for exist on the site.
+        Some(wanted) if wanted.is_empty() => (),
+        _ if limit == 0 => (),
+        _ => {
+            let filter = wanted
+                .as_deref()
+                .map_or(CategoryFilter::All, |w| category_filter(config.site, w));
+            let query = SearchQuery::new(terms.trim()).category(filter);
+
+            // The filter can be wider than the categories asked for: pages are
+            // fetched until enough results are in them.
+            let mut skipped = 0;
+            for torrent in config.client.search_results(&query) {
+                let torrent = torrent.map_err(|e| (900, e.to_string()))?;
+                if wanted
+                    .as_ref()
+                    .is_some_and(|w| !w.contains(&torrent.category))
+                {
+                    continue;
+                }
+                if skipped < offset {
+                    skipped += 1;
+                } else {
+                    torrents.push(torrent);
+                    if torrents.len() == limit {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(feed(config, torrents.into_iter()))
+}
+
+// Narrowest filter Nyaa.si can apply that contains all the categories.
+// Searches for categories of different top-level categories fall back to
+// every category; the results are filtered afterwards.
+fn category_filter(site: Site, categories: &[Category]) -> CategoryFilter {
+    if let [category] = categories {
+        return CategoryFilter::Only(*category);
+    }
+
+    (1..=6)
+        .filter_map(|n| CategoryFilter::from_site_code(site, &format!("{}_0", n)))
+        .find(|filter| categories.iter().all(|c| filter.contains(*c)))
+        .unwrap_or(CategoryFilter::All)
+}
+
+// Torznab category of a Nyaa.si category.
+fn torznab_category(category: Category) -> u32 {
+    match category {
+        Category::Anime(_) => 5070,
+        Category::Audio(Audio::Lossless) => 3040,
+        Category::Audio(Audio::Lossy) => 3010,
+        Category::Literature(_) => 7030,
+        Category::LiveAction(_) => 5000,
+        Category::Pictures(_) => 8000,
+        Category::Software(Software::Applications) => 4000,
+        Category::Software(Software::Games) => 4050,
+        Category::Art(_) | Category::RealLife(_) => 6000,
+    }
+}
+
+// Whether a Torznab category (or its parent) is the one of a Nyaa.si
+// category.
+fn matches(id: u32, category: Category) -> bool {
+    let torznab = torznab_category(category);
+
+    id == torznab || id == torznab / 1000 * 1000
+}
+
+fn caps(site: Site) -> Vec<u8> {
+    let used = site.categories().map(torznab_category).collect::<Vec<_>>();
+    let used = |id: u32| used.iter().any(|u| *u == id || *u / 1000 * 1000 == id);
+
+    write_xml(|w| {
+        w.write(XmlEvent::start_element("caps"))?;
+        w.write(XmlEvent::start_element("server").attr("title", "nyaadesu"))?;
+        w.write(XmlEvent::end_element())?;
+        w.write(
+            XmlEvent::start_element("limits")
+                .attr("default", &DEFAULT_LIMIT.to_string())
+                .attr("max", &MAX_LIMIT.to_string()),
+        )?;
+        w.write(XmlEvent::end_element())?;
+
+        w.write(XmlEvent::start_element("searching"))?;
+        for (function, params) in [("search", "q"), ("tv-search", "q,season,ep")].iter() {
+            w.write(
+                XmlEvent::start_element(*function)
+                    .attr("available", "yes")
+                    .attr("supportedParams", params),
+            )?;
+            w.write(XmlEvent::end_element())?;
+        }
+        w.write(XmlEvent::end_element())?;
+
+        w.write(XmlEvent::start_element("categories"))?;
+        for (id, _, name) in TORZNAB_CATEGORIES
+            .iter()
+            .filter(|(id, parent, _)| parent.is_none() && used(*id))
+        {
+            w.write(
+                XmlEvent::start_element("category")
+                    .attr("id", &id.to_string())
+                    .attr("name", name),
+            )?;
+            for (sub_id, _, sub_name) in TORZNAB_CATEGORIES
+                .iter()
+                .filter(|(sub_id, parent, _)| *parent == Some(*id) && used(*sub_id))
+            {
+                w.write(
+                    XmlEvent::start_element("subcat")
+                        .attr("id", &sub_id.to_string())
+                        .attr("name", sub_name),
+                )?;
+                w.write(XmlEvent::end_element())?;
+            }
+            w.write(XmlEvent::end_element())?;
+        }
+        w.write(XmlEvent::end_element())?;
+
+        w.write(XmlEvent::end_element())
+    })
+}
+
+fn feed<I: Iterator<Item = Torrent>>(config: &Config, torrents: I) -> Vec<u8> {
+    write_xml(|w| {
+        w.write(
+            XmlEvent::start_element("rss")
+                .attr("version", "2.0")
+                .ns("torznab", TORZNAB_NS),
+        )?;
+        w.write(XmlEvent::start_element("channel"))?;
+        text_element(w, "title", "nyaadesu")?;
+        text_element(w, "link", config.client.base_url())?;
+
+        for t in torrents {
+            item(w, &t)?;
+        }
+
+        w.write(XmlEvent::end_element())?;
+        w.write(XmlEvent::end_element())
+    })
+}
+
+fn item<W: std::io::Write>(w: &mut EventWriter<W>, t: &Torrent) -> xml::writer::Result<()> {
+    let category = torznab_category(t.category).to_string();
+    let view_page = t.view_page().unwrap_or_default();
+    let download = t
+        .torrent_file()
+        .or_else(|| t.magnet_link())
+        .unwrap_or_default();
+
+    w.write(XmlEvent::start_element("item"))?;
+    text_element(w, "title", &t.name)?;
+    text_element(w, "guid", &view_page)?;
+    text_element(w, "link", &download)?;
+    text_element(w, "comments", &view_page)?;
+    text_element(w, "pubDate", &t.date.to_rfc2822())?;
+    text_element(w, "size", &t.size.bytes().to_string())?;
+    text_element(w, "category", &category)?;
+    w.write(
+        XmlEvent::start_element("enclosure")
+            .attr("url", &download)
+            .attr("length", &t.size.bytes().to_string())
+            .attr("type", "application/x-bittorrent"),
+    )?;
+    w.write(XmlEvent::end_element())?;
+
+    let mut attrs = vec![
+        ("category", category),
+        ("seeders", t.seeders.to_string()),
+        ("peers", t.seeders.saturating_add(t.leechers).to_string()),
+        ("grabs", t.completed_downloads.to_string()),
+        ("downloadvolumefactor", "1".to_string()),
+        ("uploadvolumefactor", "1".to_string()),
+    ];
+    if let Some(magnet) = t.magnet() {
+        attrs.push(("infohash", magnet.info_hash.to_hex()));
+    }
+    if let Some(magnet) = t.magnet_link() {
+        attrs.push(("magneturl", magnet));
+    }
+    for (name, value) in attrs.iter() {
+        w.write(
+            XmlEvent::start_element("torznab:attr")
+                .attr("name", name)
+                .attr("value", value),
+        )?;
+        w.write(XmlEvent::end_element())?;
+    }
+
+    w.write(XmlEvent::end_element())
+}
+
+fn error(code: u16, description: &str) -> Answer {
+    let body = write_xml(|w| {
+        w.write(
+            XmlEvent::start_element("error")
+                .attr("code", &code.to_string())
+                .attr("description", description),
+        )?;
+        w.write(XmlEvent::end_element())
+    });
+
+    Answer {
+        status: 200,
+        content_type: "application/xml; charset=utf-8",
+        body,
+    }
+}
+
+fn text_element<W: std::io::Write>(
+    w: &mut EventWriter<W>,
+    name: &str,
+    text: &str,
+) -> xml::writer::Result<()> {
+    w.write(XmlEvent::start_element(name))?;
+    w.write(XmlEvent::characters(text))?;
+    w.write(XmlEvent::end_element())
+}
+
+// Write an xml document to a buffer.
+fn write_xml<F>(f: F) -> Vec<u8>
+where
+    F: FnOnce(&mut EventWriter<&mut Vec<u8>>) -> xml::writer::Result<()>,
+{
+    let mut body = vec![];
+    let mut writer = EmitterConfig::new()
+        .perform_indent(true)
+        .create_writer(&mut body);
+
+    // Writing to a Vec can't fail and the documents are well formed.
+    let _ = f(&mut writer);
+    drop(writer);
+
+    body
+}
+
+// Decode the parameters of a query string.
+fn query_params(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (key, value) = match p.find('=') {
+                Some(i) => (&p[..i], &p[i + 1..]),
+                None => (p, ""),
+            };
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn decode(s: &str) -> String {
+    percent_encoding::percent_decode_str(&s.replace('+', " "))
+        .decode_utf8_lossy()
+        .into_owned()
+}
@@ -0,0 +1,225 @@
+use nyaadesu::*;
+
+/// Enum that encodes what the user asked for.
+pub enum Command {
+    /// Print the usage.
+    Help,
+    /// Search for torrents.
+    Search { query: SearchQuery, format: Format },
+    /// Show the details and the files of a torrent.
+    View { id: u32, format: Format },
+    /// Print the magnet link of a torrent.
+    Magnet { id: u32 },
+    /// Save the .torrent file of a torrent (to stdout if `output` is "-").
+    Download { id: u32, output: Option<String> },
+}
+
+/// Enum that encodes how results are printed.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Table,
+    Jsonl,
+    Csv,
+}
+
+/// Struct that represents the parsed command line.
+pub struct Args {
+    pub site: Site,
+    pub base_url: Option<String>,
+    pub command: Command,
+}
+
+// Options that take a value, with their short forms.
+const OPTIONS: [(&str, Option<&str>); 9] = [
+    ("--site", None),
+    ("--base-url", None),
+    ("--category", Some("-c")),
+    ("--filter", Some("-f")),
+    ("--sort", Some("-s")),
+    ("--order", Some("-o")),
+    ("--limit", Some("-n")),
+    ("--format", None),
+    ("--output", Some("-O")),
+];
+
+// Number of results of a search when no limit is given: one page of Nyaa.si.
+const DEFAULT_LIMIT: usize = 75;
+
+impl Args {
+    /// Parse the command line arguments (without the program name).
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+        let mut positionals = vec![];
+        let mut options = vec![];
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                positionals.extend(args.by_ref());
+            } else if arg == "-h" || arg == "--help" {
+                return Ok(Args {
+                    site: Site::Nyaa,
+                    base_url: None,
+                    command: Command::Help,
+                });
+            } else if arg.starts_with('-') && arg.len() > 1 {
+                let (name, value) = match arg.find('=') {
+                    Some(i) => (&arg[..i], Some(arg[i + 1..].to_string())),
+                    None => (&arg[..], None),
+                };
+                let long = match OPTIONS
+                    .iter()
+                    .find(|(long, short)| *long == name || *short == Some(name))
+                {
+                    Some((long, _)) => *long,
+                    // Nyaa.si leaves out results with terms that start with a
+                    // "-" (e.g. "-raw"), so after the first search term
+                    // unknown options are terms.
+                    None if positionals.len() > 1 && positionals[0] == "search" => {
+                        positionals.push(arg);
+                        continue;
+                    }
+                    None => return Err(format!("unknown option {}", name)),
+                };
+                let value = match value {
+                    Some(value) => value,
+                    None => args
+                        .next()
+                        .ok_or_else(|| format!("{} needs a value", name))?,
+                };
+                options.push((long, value));
+            } else {
+                positionals.push(arg);
+            }
+        }
+
+        let mut options = Options(options);
+        let site = match options.take("--site") {
+            Some(site) => site.parse()?,
+            None => Site::Nyaa,
+        };
+        let base_url = options.take("--base-url");
+
+        let mut positionals = positionals.into_iter();
+        let command = match positionals.next().as_deref() {
+            None | Some("help") => Command::Help,
+            Some("search") => {
+                let terms = positionals.by_ref().collect::<Vec<_>>().join(" ");
+                Command::Search {
+                    query: search_query(&terms, site, &mut options)?,
+                    format: format(&mut options)?,
+                }
+            }
+            Some("view") => Command::View {
+                id: id(positionals.next())?,
+                format: format(&mut options)?,
+            },
+            Some("magnet") => Command::Magnet {
+                id: id(positionals.next())?,
+            },
+            Some("download") => Command::Download {
+                id: id(positionals.next())?,
+                output: options.take("--output"),
+            },
+            Some(command) => return Err(format!("unknown command {:?}", command)),
+        };
+
+        if let Some(extra) = positionals.next() {
+            return Err(format!("unexpected argument {:?}", extra));
+        }
+        if let Some((option, _)) = options.0.first() {
+            return Err(format!("{} can't be used with this command", option));
+        }
+
+        Ok(Args {
+            site,
+            base_url,
+            command,
+        })
+    }
+}
+
+// Options given on the command line, removed as commands use them so that
+// the ones left over can be reported.
+struct Options(Vec<(&'static str, String)>);
+
+impl Options {
+    // Take the last value of an option.
+    fn take(&mut self, name: &str) -> Option<String> {
+        let mut value = None;
+        self.0.retain(|(option, v)| {
+            if *option == name {
+                value = Some(v.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        value
+    }
+}
+
+fn search_query(terms: &str, site: Site, options: &mut Options) -> Result<SearchQuery, String> {
+    let mut query = SearchQuery::new(terms);
+
+    if let Some(category) = options.take("--category") {
+        let filter = CategoryFilter::from_site_code(site, &category)
+            .map(Ok)
+            .unwrap_or_else(|| category.parse())
+            .map_err(|e| e.to_string())?;
+        if filter.site().is_some_and(|s| s != site) {
+            return Err(format!("{} has no category {:?}", site, category));
+        }
+        query = query.category(filter);
+    }
+    if let Some(filter) = options.take("--filter") {
+        query = query.filter(match filter.as_str() {
+            "none" => Filter::NoFilter,
+            "no-remakes" => Filter::NoRemakes,
+            "trusted-only" => Filter::TrustedOnly,
+            _ => return Err(format!("unknown filter {:?}", filter)),
+        });
+    }
+    if let Some(sort) = options.take("--sort") {
+        query = query.sort(match sort.as_str() {
+            "date" => Sort::Date,
+            "size" => Sort::Size,
+            "seeders" => Sort::Seeders,
+            "leechers" => Sort::Leechers,
+            "downloads" => Sort::Downloads,
+            "comments" => Sort::Comments,
+            _ => return Err(format!("unknown sort key {:?}", sort)),
+        });
+    }
+    if let Some(order) = options.take("--order") {
+        query = query.order(match order.as_str() {
+            "asc" => Order::Ascending,
+            "desc" => Order::Descending,
+            _ => return Err(format!("unknown order {:?}", order)),
+        });
+    }
+
+    let limit = match options.take("--limit") {
+        Some(limit) => limit
+            .parse()
+            .map_err(|_| format!("invalid limit {:?}", limit))?,
+        None => DEFAULT_LIMIT,
+    };
+
+    Ok(query.limit(limit))
+}
+
+fn format(options: &mut Options) -> Result<Format, String> {
+    match options.take("--format").as_deref() {
+        None | Some("table") => Ok(Format::Table),
+        Some("jsonl") => Ok(Format::Jsonl),
+        Some("csv") => Ok(Format::Csv),
+        Some(format) => Err(format!("unknown format {:?}", format)),
+    }
+}
+
+fn id(id: Option<String>) -> Result<u32, String> {
+    let id = id.ok_or("missing torrent id")?;
+
+    id.parse()
+        .map_err(|_| format!("invalid torrent id {:?}", id))
+}
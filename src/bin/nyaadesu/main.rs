@@ -0,0 +1,142 @@
+//! Command-line interface to search Nyaa.si, look at torrents and download
+//! them.
+
+extern crate nyaadesu;
+extern crate serde;
+extern crate serde_json;
+
+mod args;
+mod output;
+
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+
+use nyaadesu::*;
+
+use crate::args::{Args, Command};
+
+const USAGE: &str = "\
+Usage: nyaadesu [--site nyaa|sukebei] <command> [options]
+
+Commands:
+  search [options] <terms>...   Search for torrents
+  view [--format FORMAT] <id>   Show the details and the files of a torrent
+  magnet <id>                   Print the magnet link of a torrent
+  download [-O FILE] <id>       Save the .torrent file of a torrent
+                                (to <id>.torrent, or to stdout with -O -)
+
+Search options:
+  -c, --category CATEGORY  Code or name (e.g. 1_2, \"Anime\" or \"Anime - Raw\")
+  -f, --filter FILTER      none, no-remakes or trusted-only
+  -s, --sort KEY           date, size, seeders, leechers, downloads or comments
+  -o, --order ORDER        asc or desc
+  -n, --limit N            Maximum number of results (default: 75)
+
+Terms that start with - (e.g. -raw) leave out the results that have them.
+They need a term before them, or -- before all the terms.
+
+Output:
+  --format FORMAT          table (default), jsonl or csv
+
+Other options:
+  --site SITE              nyaa (default) or sukebei
+  --base-url URL           Url of the site (e.g. a mirror)
+  -h, --help               Print this help
+";
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("nyaadesu: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match run(args) {
+        Ok(()) => (),
+        // The output was closed (e.g. piped to `head`): nothing left to do.
+        Err(Failure::Output(ref e)) if e.kind() == io::ErrorKind::BrokenPipe => (),
+        Err(e) => {
+            eprintln!("nyaadesu: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), Failure> {
+    let mut builder = NyaaClient::builder().site(args.site);
+    if let Some(base_url) = &args.base_url {
+        builder = builder.base_url(base_url);
+    }
+    let client = builder.build()?;
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    match args.command {
+        Command::Help => out.write_all(USAGE.as_bytes())?,
+        Command::Search { query, format } => {
+            let torrents = client.search(&query)?;
+            output::torrents(&mut out, &torrents, format)?;
+        }
+        Command::View { id, format } => {
+            let details = client.torrent_details(id)?;
+            output::details(&mut out, &details, format)?;
+        }
+        Command::Magnet { id } => {
+            let magnet = client
+                .torrent_details(id)?
+                .torrent
+                .magnet_link()
+                .ok_or(Failure::NoMagnet(id))?;
+            writeln!(out, "{}", magnet)?;
+        }
+        Command::Download { id, output } => {
+            let torrent = client.download_torrent(id)?;
+            match output.as_deref() {
+                Some("-") => out.write_all(&torrent)?,
+                path => {
+                    let path = path.map_or_else(|| format!("{}.torrent", id), str::to_string);
+                    fs::write(&path, &torrent).map_err(|e| Failure::Save(path.clone(), e))?;
+                    eprintln!("Saved {}", path);
+                }
+            }
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+// Enum that encodes why a command failed.
+enum Failure {
+    Nyaa(Error),
+    NoMagnet(u32),
+    Save(String, io::Error),
+    Output(io::Error),
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Failure::Nyaa(e) => e.fmt(f),
+            Failure::NoMagnet(id) => write!(f, "torrent {} has no magnet link", id),
+            Failure::Save(path, e) => write!(f, "could not save {}: {}", path, e),
+            Failure::Output(e) => write!(f, "could not write output: {}", e),
+        }
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(e: Error) -> Failure {
+        Failure::Nyaa(e)
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Failure {
+        Failure::Output(e)
+    }
+}
@@ -0,0 +1,186 @@
+use std::io::{self, Write};
+
+use nyaadesu::*;
+
+use crate::args::Format;
+
+/// Print search results.
+pub fn torrents<W: Write>(out: &mut W, torrents: &[Torrent], format: Format) -> io::Result<()> {
+    match format {
+        Format::Table => {
+            writeln!(
+                out,
+                "{:>8}  {:>10}  {:<16}  {:>5}  {:>5}  {:>6}  NAME",
+                "ID", "SIZE", "DATE", "S", "L", "C"
+            )?;
+            for t in torrents {
+                writeln!(
+                    out,
+                    "{:>8}  {:>10}  {:<16}  {:>5}  {:>5}  {:>6}  {}",
+                    t.id,
+                    t.size.to_string(),
+                    t.date.relative(),
+                    t.seeders,
+                    t.leechers,
+                    t.completed_downloads,
+                    t.name
+                )?;
+            }
+        }
+        Format::Jsonl => {
+            for t in torrents {
+                json_line(out, t)?;
+            }
+        }
+        Format::Csv => {
+            csv_line(
+                out,
+                &[
+                    "id",
+                    "category",
+                    "name",
+                    "size",
+                    "date",
+                    "seeders",
+                    "leechers",
+                    "completed_downloads",
+                    "comments",
+                    "status",
+                    "torrent_file",
+                    "magnet",
+                ],
+            )?;
+            for t in torrents {
+                csv_line(
+                    out,
+                    &[
+                        &t.id.to_string(),
+                        t.category.code(),
+                        &t.name,
+                        &t.size.bytes().to_string(),
+                        &t.date.to_string(),
+                        &t.seeders.to_string(),
+                        &t.leechers.to_string(),
+                        &t.completed_downloads.to_string(),
+                        &t.comments.to_string(),
+                        status(t.status),
+                        &t.torrent_file().unwrap_or_default(),
+                        &t.magnet_link().unwrap_or_default(),
+                    ],
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the details of a torrent. As CSV only its files are printed, one
+/// per line with their full path.
+pub fn details<W: Write>(out: &mut W, details: &TorrentDetails, format: Format) -> io::Result<()> {
+    let t = &details.torrent;
+
+    match format {
+        Format::Table => {
+            writeln!(out, "Name:        {}", t.name)?;
+            writeln!(out, "Category:    {}", t.category)?;
+            writeln!(out, "Size:        {}", t.size)?;
+            writeln!(out, "Date:        {} ({})", t.date, t.date.relative())?;
+            writeln!(
+                out,
+                "Submitter:   {}",
+                details.submitter.as_deref().unwrap_or("Anonymous")
+            )?;
+            if let Some(information) = &details.information {
+                writeln!(out, "Information: {}", information)?;
+            }
+            writeln!(out, "Status:      {}", status(t.status))?;
+            writeln!(
+                out,
+                "Peers:       {} seeders, {} leechers, {} completed",
+                t.seeders, t.leechers, t.completed_downloads
+            )?;
+            writeln!(out, "Info hash:   {}", details.info_hash)?;
+            if let Some(link) = t.torrent_file() {
+                writeln!(out, "Torrent:     {}", link)?;
+            }
+            if let Some(link) = t.magnet_link() {
+                writeln!(out, "Magnet:      {}", link)?;
+            }
+            writeln!(out, "Files:")?;
+            for entry in details.files.iter() {
+                file_tree(out, entry, 1)?;
+            }
+        }
+        Format::Jsonl => json_line(out, details)?,
+        Format::Csv => {
+            csv_line(out, &["path", "size"])?;
+            for entry in details.files.iter() {
+                file_paths(out, entry, "")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn file_tree<W: Write>(out: &mut W, entry: &FileEntry, depth: usize) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+
+    match entry {
+        FileEntry::File { name, size } => writeln!(out, "{}{} ({})", indent, name, size),
+        FileEntry::Folder { name, entries } => {
+            writeln!(out, "{}{}/", indent, name)?;
+            for entry in entries {
+                file_tree(out, entry, depth + 1)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn file_paths<W: Write>(out: &mut W, entry: &FileEntry, parent: &str) -> io::Result<()> {
+    match entry {
+        FileEntry::File { name, size } => csv_line(
+            out,
+            &[&format!("{}{}", parent, name), &size.bytes().to_string()],
+        ),
+        FileEntry::Folder { name, entries } => {
+            let parent = format!("{}{}/", parent, name);
+            for entry in entries {
+                file_paths(out, entry, &parent)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn status(status: Status) -> &'static str {
+    match status {
+        Status::Normal => "normal",
+        Status::Trusted => "trusted",
+        Status::Remake => "remake",
+    }
+}
+
+fn json_line<W: Write, T: serde::Serialize>(out: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    writeln!(out)
+}
+
+// Write a CSV record: fields with commas, quotes or line breaks are quoted
+// like RFC 4180 says, but records end with "\n" to play well with shell tools.
+fn csv_line<W: Write>(out: &mut W, fields: &[&str]) -> io::Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.write_all(b",")?;
+        }
+        if field.contains(&[',', '"', '\r', '\n'][..]) {
+            write!(out, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            out.write_all(field.as_bytes())?;
+        }
+    }
+
+    out.write_all(b"\n")
+}
@@ -0,0 +1,229 @@
+extern crate reqwest;
+
+use std::time::Duration;
+
+#[cfg(feature = "async")]
+use crate::async_client::*;
+#[cfg(feature = "blocking")]
+use crate::client::*;
+use crate::retry::*;
+use crate::scraping::*;
+use crate::site::*;
+use crate::types::*;
+
+/// Builder used to configure a [`NyaaClient`] or an [`AsyncNyaaClient`].
+///
+/// [`NyaaClient`]: ./struct.NyaaClient.html
+/// [`AsyncNyaaClient`]: ./struct.AsyncNyaaClient.html
+#[derive(Debug, Clone)]
+pub struct NyaaClientBuilder {
+    base_url: String,
+    site: Option<Site>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: String,
+    proxy: Option<String>,
+    parse_mode: ParseMode,
+    retry: RetryPolicy,
+    rate_limit: Option<(u32, Duration)>,
+}
+
+impl NyaaClientBuilder {
+    /// Create a builder with the default configuration.
+    pub fn new() -> NyaaClientBuilder {
+        NyaaClientBuilder {
+            base_url: Site::Nyaa.base_url().to_string(),
+            site: None,
+            timeout: None,
+            connect_timeout: None,
+            user_agent: concat!("nyaadesu/", env!("CARGO_PKG_VERSION")).to_string(),
+            proxy: None,
+            parse_mode: ParseMode::Strict,
+            retry: RetryPolicy::none(),
+            rate_limit: None,
+        }
+    }
+
+    /// Set the url of the site to talk to (by default `https://nyaa.si`).
+    /// Unless the [`site`] is set too it is guessed from the url (see
+    /// [`Site::from_base_url`]).
+    ///
+    /// [`site`]: #method.site
+    /// [`Site::from_base_url`]: ./enum.Site.html#method.from_base_url
+    pub fn base_url(mut self, base_url: &str) -> NyaaClientBuilder {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Talk to one of the sites this library knows about. This sets the
+    /// [`base_url`] to the site's url: set it after this to talk to a mirror
+    /// of the site.
+    ///
+    /// ```no_run
+    /// let sukebei = nyaadesu::NyaaClient::builder()
+    ///     .site(nyaadesu::Site::Sukebei)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// [`base_url`]: #method.base_url
+    pub fn site(mut self, site: Site) -> NyaaClientBuilder {
+        self.site = Some(site);
+        self.base_url(site.base_url())
+    }
+
+    /// Set the timeout of each request.
+    pub fn timeout(mut self, timeout: Duration) -> NyaaClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for connecting to the site.
+    pub fn connect_timeout(mut self, timeout: Duration) -> NyaaClientBuilder {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the user agent sent with each request.
+    pub fn user_agent(mut self, user_agent: &str) -> NyaaClientBuilder {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Send every request through the proxy at the given url.
+    pub fn proxy(mut self, proxy: &str) -> NyaaClientBuilder {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Choose what to do with rows of search results that can't be scraped
+    /// (by default a single bad row fails the whole search). In
+    /// [`ParseMode::Lenient`] [`NyaaClient::search`] skips those rows and
+    /// [`NyaaClient::search_page`] reports them.
+    ///
+    /// [`ParseMode::Lenient`]: ./enum.ParseMode.html#variant.Lenient
+    /// [`NyaaClient::search`]: ./struct.NyaaClient.html#method.search
+    /// [`NyaaClient::search_page`]: ./struct.NyaaClient.html#method.search_page
+    pub fn parse_mode(mut self, mode: ParseMode) -> NyaaClientBuilder {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Try requests again when they fail with an error that may go away (e.g.
+    /// 429 or 503) as described by a [`RetryPolicy`] (by default requests are
+    /// only tried once).
+    ///
+    /// ```no_run
+    /// let client = nyaadesu::NyaaClient::builder()
+    ///     .retry(nyaadesu::RetryPolicy::new().max_retries(5))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// [`RetryPolicy`]: ./struct.RetryPolicy.html
+    pub fn retry(mut self, policy: RetryPolicy) -> NyaaClientBuilder {
+        self.retry = policy;
+        self
+    }
+
+    /// Send at most `requests` requests every `per` (e.g. 2 every second).
+    /// Requests over the limit wait for their turn instead of failing. Up to
+    /// `requests` requests can go out at once after the client was idle.
+    ///
+    /// The limit is shared by every call on the client and on its clones,
+    /// including the pages of a search and retries.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// let client = nyaadesu::NyaaClient::builder()
+    ///     .rate_limit(2, Duration::from_secs(1))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn rate_limit(mut self, requests: u32, per: Duration) -> NyaaClientBuilder {
+        self.rate_limit = Some((requests, per));
+        self
+    }
+
+    /// Create a blocking client.
+    #[cfg(feature = "blocking")]
+    pub fn build(self) -> Result<NyaaClient, Error> {
+        let mut builder =
+            reqwest::blocking::Client::builder().user_agent(self.user_agent_header()?);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.build_proxy()? {
+            builder = builder.proxy(proxy);
+        }
+
+        Ok(NyaaClient {
+            client: builder.build()?,
+            rate_limiter: self.rate_limiter(),
+            site: self.resolved_site(),
+            base_url: self.base_url,
+            parse_mode: self.parse_mode,
+            retry: self.retry,
+        })
+    }
+
+    /// Create an async client.
+    #[cfg(feature = "async")]
+    pub fn build_async(self) -> Result<AsyncNyaaClient, Error> {
+        let mut builder = reqwest::Client::builder().user_agent(self.user_agent_header()?);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.build_proxy()? {
+            builder = builder.proxy(proxy);
+        }
+
+        Ok(AsyncNyaaClient {
+            client: builder.build()?,
+            rate_limiter: self.rate_limiter(),
+            site: self.resolved_site(),
+            base_url: self.base_url,
+            parse_mode: self.parse_mode,
+            retry: self.retry,
+        })
+    }
+
+    // The site that was set or the one of the base url.
+    fn resolved_site(&self) -> Site {
+        self.site
+            .unwrap_or_else(|| Site::from_base_url(&self.base_url))
+    }
+
+    // Build the rate limiter, if any.
+    fn rate_limiter(&self) -> Option<RateLimiter> {
+        self.rate_limit
+            .map(|(requests, per)| RateLimiter::new(requests, per))
+    }
+
+    // Validate the user agent.
+    fn user_agent_header(&self) -> Result<reqwest::header::HeaderValue, Error> {
+        reqwest::header::HeaderValue::from_str(&self.user_agent)
+            .map_err(|_| Error::Configuration(format!("invalid user agent: {:?}", self.user_agent)))
+    }
+
+    // Build the proxy, if any.
+    fn build_proxy(&self) -> Result<Option<reqwest::Proxy>, Error> {
+        match &self.proxy {
+            Some(proxy) => Ok(Some(reqwest::Proxy::all(proxy)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Default for NyaaClientBuilder {
+    fn default() -> NyaaClientBuilder {
+        NyaaClientBuilder::new()
+    }
+}
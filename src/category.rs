@@ -0,0 +1,461 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::site::*;
+
+// Define the enum of the variants of a top-level category together with their
+// Nyaa.si codes and names.
+macro_rules! sub_category {
+    (
+        $(#[$doc:meta])*
+        $name:ident ($top:literal) {
+            $($(#[$variant_doc:meta])* $variant:ident => $code:literal, $text:literal;)+
+        }
+    ) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $name {
+            $($(#[$variant_doc])* $variant,)+
+        }
+
+        impl $name {
+            /// Nyaa.si code of the category (the value of the `c` query
+            /// parameter).
+            pub fn code(self) -> &'static str {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+
+            /// Category with the given Nyaa.si code.
+            pub fn from_code(code: &str) -> Option<$name> {
+                match code {
+                    $($code => Some($name::$variant),)+
+                    _ => None,
+                }
+            }
+
+            /// Name of the category as Nyaa.si shows it, without the
+            /// top-level category (e.g. "English-translated").
+            pub fn name(self) -> &'static str {
+                match self {
+                    $($name::$variant => $text,)+
+                }
+            }
+
+            /// Iterate over every variant.
+            pub fn all() -> impl Iterator<Item = $name> {
+                [$($name::$variant,)+].iter().copied()
+            }
+
+            // Name of the top-level category.
+            const TOP: &'static str = $top;
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = UnknownCategory;
+
+            /// Parse either a code (e.g. "1_2") or a name (e.g.
+            /// "English-translated", case insensitive).
+            fn from_str(s: &str) -> Result<$name, UnknownCategory> {
+                $name::from_code(s)
+                    .or_else(|| $name::all().find(|c| c.name().eq_ignore_ascii_case(s)))
+                    .ok_or_else(|| UnknownCategory(s.to_string()))
+            }
+        }
+
+        impl From<$name> for Category {
+            fn from(category: $name) -> Category {
+                Category::$name(category)
+            }
+        }
+    };
+}
+
+/// Enum that encodes a torrent's category.
+///
+/// Categories are displayed the way Nyaa.si shows them (e.g. "Anime -
+/// English-translated") and they can be parsed back from either that or their
+/// code:
+///
+/// ```
+/// use nyaadesu::{Anime, Category};
+///
+/// let category: Category = "1_2".parse().unwrap();
+/// assert_eq!(category, Category::Anime(Anime::EnglishTranslated));
+/// assert_eq!(category.to_string(), "Anime - English-translated");
+/// assert_eq!(category.code(), "1_2");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    Anime(Anime),
+    Audio(Audio),
+    Literature(Literature),
+    LiveAction(LiveAction),
+    Pictures(Pictures),
+    Software(Software),
+    /// Sukebei only.
+    Art(Art),
+    /// Sukebei only.
+    RealLife(RealLife),
+}
+
+impl Category {
+    /// Nyaa.si code of the category (e.g. "1_2" for English-translated
+    /// anime).
+    pub fn code(self) -> &'static str {
+        match self {
+            Category::Anime(c) => c.code(),
+            Category::Audio(c) => c.code(),
+            Category::Literature(c) => c.code(),
+            Category::LiveAction(c) => c.code(),
+            Category::Pictures(c) => c.code(),
+            Category::Software(c) => c.code(),
+            Category::Art(c) => c.code(),
+            Category::RealLife(c) => c.code(),
+        }
+    }
+
+    /// Category with the given Nyaa.si code. Sukebei uses the same codes for
+    /// different categories: use [`from_site_code`] for its codes.
+    ///
+    /// [`from_site_code`]: #method.from_site_code
+    pub fn from_code(code: &str) -> Option<Category> {
+        Category::from_site_code(Site::Nyaa, code)
+    }
+
+    /// Category with the given code on the given site.
+    pub fn from_site_code(site: Site, code: &str) -> Option<Category> {
+        site.categories().find(|c| c.code() == code)
+    }
+
+    /// Name of the top-level category (e.g. "Live Action").
+    pub fn top_level_name(self) -> &'static str {
+        match self {
+            Category::Anime(_) => Anime::TOP,
+            Category::Audio(_) => Audio::TOP,
+            Category::Literature(_) => Literature::TOP,
+            Category::LiveAction(_) => LiveAction::TOP,
+            Category::Pictures(_) => Pictures::TOP,
+            Category::Software(_) => Software::TOP,
+            Category::Art(_) => Art::TOP,
+            Category::RealLife(_) => RealLife::TOP,
+        }
+    }
+
+    /// Site the category belongs to.
+    pub fn site(self) -> Site {
+        match self {
+            Category::Art(_) | Category::RealLife(_) => Site::Sukebei,
+            _ => Site::Nyaa,
+        }
+    }
+
+    /// Name of the category without the top-level category (e.g.
+    /// "English-translated").
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Anime(c) => c.name(),
+            Category::Audio(c) => c.name(),
+            Category::Literature(c) => c.name(),
+            Category::LiveAction(c) => c.name(),
+            Category::Pictures(c) => c.name(),
+            Category::Software(c) => c.name(),
+            Category::Art(c) => c.name(),
+            Category::RealLife(c) => c.name(),
+        }
+    }
+
+    /// Iterate over every category of both Nyaa.si and Sukebei (see
+    /// [`Site::categories`] for the categories of a single site).
+    ///
+    /// [`Site::categories`]: ./enum.Site.html#method.categories
+    pub fn all() -> impl Iterator<Item = Category> {
+        Site::Nyaa.categories().chain(Site::Sukebei.categories())
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} - {}", self.top_level_name(), self.name())
+    }
+}
+
+impl FromStr for Category {
+    type Err = UnknownCategory;
+
+    /// Parse either a Nyaa.si code (e.g. "1_2") or a name (e.g. "Anime -
+    /// English-translated" or "Art - Manga", case insensitive).
+    fn from_str(s: &str) -> Result<Category, UnknownCategory> {
+        Category::from_code(s)
+            .or_else(|| Category::all().find(|c| c.to_string().eq_ignore_ascii_case(s)))
+            .ok_or_else(|| UnknownCategory(s.to_string()))
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of anime torrents.
+    Anime("Anime") {
+        AnimeMusicVideo => "1_1", "Anime Music Video";
+        EnglishTranslated => "1_2", "English-translated";
+        NonEnglishTranslated => "1_3", "Non-English-translated";
+        Raw => "1_4", "Raw";
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of audio torrents.
+    Audio("Audio") {
+        Lossless => "2_1", "Lossless";
+        Lossy => "2_2", "Lossy";
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of literature torrents.
+    Literature("Literature") {
+        EnglishTranslated => "3_1", "English-translated";
+        NonEnglishTranslated => "3_2", "Non-English-translated";
+        Raw => "3_3", "Raw";
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of live action torrents.
+    LiveAction("Live Action") {
+        EnglishTranslated => "4_1", "English-translated";
+        IdolPromotionalVideo => "4_2", "Idol/Promotional Video";
+        NonEnglishTranslated => "4_3", "Non-English-translated";
+        Raw => "4_4", "Raw";
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of pictures torrents.
+    Pictures("Pictures") {
+        Graphics => "5_1", "Graphics";
+        Photos => "5_2", "Photos";
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of software torrents.
+    Software("Software") {
+        Applications => "6_1", "Applications";
+        Games => "6_2", "Games";
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of art torrents (Sukebei only).
+    Art("Art") {
+        Anime => "1_1", "Anime";
+        Doujinshi => "1_2", "Doujinshi";
+        Games => "1_3", "Games";
+        Manga => "1_4", "Manga";
+        Pictures => "1_5", "Pictures";
+    }
+}
+
+sub_category! {
+    /// Enum that encodes variants of real life torrents (Sukebei only).
+    RealLife("Real Life") {
+        PhotobooksAndPictures => "2_1", "Photobooks and Pictures";
+        Videos => "2_2", "Videos";
+    }
+}
+
+/// Enum that encodes the categories a search can be restricted to: either
+/// everything, a whole top-level category or a single [`Category`].
+///
+/// Like categories, filters are parsed from and displayed as Nyaa.si codes
+/// and names:
+///
+/// ```
+/// use nyaadesu::CategoryFilter;
+///
+/// let filter: CategoryFilter = "1_0".parse().unwrap();
+/// assert_eq!(filter, CategoryFilter::Anime);
+/// assert_eq!("anime".parse(), Ok(CategoryFilter::Anime));
+/// ```
+///
+/// [`Category`]: ./enum.Category.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CategoryFilter {
+    /// Every category.
+    All,
+    /// All of Anime.
+    Anime,
+    /// All of Audio.
+    Audio,
+    /// All of Literature.
+    Literature,
+    /// All of Live Action.
+    LiveAction,
+    /// All of Pictures.
+    Pictures,
+    /// All of Software.
+    Software,
+    /// All of Art (Sukebei only).
+    Art,
+    /// All of Real Life (Sukebei only).
+    RealLife,
+    /// A single category.
+    Only(Category),
+}
+
+impl CategoryFilter {
+    /// Nyaa.si code of the filter (the value of the `c` query parameter).
+    pub fn code(self) -> &'static str {
+        match self {
+            CategoryFilter::All => "0_0",
+            CategoryFilter::Anime => "1_0",
+            CategoryFilter::Audio => "2_0",
+            CategoryFilter::Literature => "3_0",
+            CategoryFilter::LiveAction => "4_0",
+            CategoryFilter::Pictures => "5_0",
+            CategoryFilter::Software => "6_0",
+            CategoryFilter::Art => "1_0",
+            CategoryFilter::RealLife => "2_0",
+            CategoryFilter::Only(category) => category.code(),
+        }
+    }
+
+    /// Filter with the given Nyaa.si code. Use [`from_site_code`] for
+    /// Sukebei's codes.
+    ///
+    /// [`from_site_code`]: #method.from_site_code
+    pub fn from_code(code: &str) -> Option<CategoryFilter> {
+        CategoryFilter::from_site_code(Site::Nyaa, code)
+    }
+
+    /// Filter with the given code on the given site.
+    pub fn from_site_code(site: Site, code: &str) -> Option<CategoryFilter> {
+        let filter = match (site, code) {
+            (_, "0_0") => CategoryFilter::All,
+            (Site::Sukebei, "1_0") => CategoryFilter::Art,
+            (Site::Sukebei, "2_0") => CategoryFilter::RealLife,
+            (Site::Sukebei, _) => CategoryFilter::Only(Category::from_site_code(site, code)?),
+            (Site::Nyaa, "1_0") => CategoryFilter::Anime,
+            (Site::Nyaa, "2_0") => CategoryFilter::Audio,
+            (Site::Nyaa, "3_0") => CategoryFilter::Literature,
+            (Site::Nyaa, "4_0") => CategoryFilter::LiveAction,
+            (Site::Nyaa, "5_0") => CategoryFilter::Pictures,
+            (Site::Nyaa, "6_0") => CategoryFilter::Software,
+            (Site::Nyaa, _) => CategoryFilter::Only(Category::from_code(code)?),
+        };
+
+        Some(filter)
+    }
+
+    /// Site the filter belongs to, `None` for [`All`] which every site has.
+    ///
+    /// [`All`]: #variant.All
+    pub fn site(self) -> Option<Site> {
+        match self {
+            CategoryFilter::All => None,
+            CategoryFilter::Art | CategoryFilter::RealLife => Some(Site::Sukebei),
+            CategoryFilter::Only(category) => Some(category.site()),
+            _ => Some(Site::Nyaa),
+        }
+    }
+
+    /// Whether a category passes the filter.
+    pub fn contains(self, category: Category) -> bool {
+        match (self, category) {
+            (CategoryFilter::All, _) => true,
+            (CategoryFilter::Anime, Category::Anime(_)) => true,
+            (CategoryFilter::Audio, Category::Audio(_)) => true,
+            (CategoryFilter::Literature, Category::Literature(_)) => true,
+            (CategoryFilter::LiveAction, Category::LiveAction(_)) => true,
+            (CategoryFilter::Pictures, Category::Pictures(_)) => true,
+            (CategoryFilter::Software, Category::Software(_)) => true,
+            (CategoryFilter::Art, Category::Art(_)) => true,
+            (CategoryFilter::RealLife, Category::RealLife(_)) => true,
+            (CategoryFilter::Only(only), category) => only == category,
+            _ => false,
+        }
+    }
+
+    // Name of the filter.
+    fn name(self) -> &'static str {
+        match self {
+            CategoryFilter::All => "All categories",
+            CategoryFilter::Anime => Anime::TOP,
+            CategoryFilter::Audio => Audio::TOP,
+            CategoryFilter::Literature => Literature::TOP,
+            CategoryFilter::LiveAction => LiveAction::TOP,
+            CategoryFilter::Pictures => Pictures::TOP,
+            CategoryFilter::Software => Software::TOP,
+            CategoryFilter::Art => Art::TOP,
+            CategoryFilter::RealLife => RealLife::TOP,
+            CategoryFilter::Only(_) => "",
+        }
+    }
+}
+
+impl fmt::Display for CategoryFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CategoryFilter::Only(category) => category.fmt(f),
+            filter => f.write_str(filter.name()),
+        }
+    }
+}
+
+impl FromStr for CategoryFilter {
+    type Err = UnknownCategory;
+
+    /// Parse either a Nyaa.si code (e.g. "1_0" or "1_2"), the name of a top-level
+    /// category (e.g. "Anime"), "All" or the name of a category (case
+    /// insensitive).
+    fn from_str(s: &str) -> Result<CategoryFilter, UnknownCategory> {
+        if let Some(filter) = CategoryFilter::from_code(s) {
+            return Ok(filter);
+        }
+
+        let top_levels = [
+            CategoryFilter::All,
+            CategoryFilter::Anime,
+            CategoryFilter::Audio,
+            CategoryFilter::Literature,
+            CategoryFilter::LiveAction,
+            CategoryFilter::Pictures,
+            CategoryFilter::Software,
+            CategoryFilter::Art,
+            CategoryFilter::RealLife,
+        ];
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(CategoryFilter::All);
+        }
+        if let Some(filter) = top_levels.iter().find(|f| f.name().eq_ignore_ascii_case(s)) {
+            return Ok(*filter);
+        }
+
+        s.parse().map(CategoryFilter::Only)
+    }
+}
+
+impl From<Category> for CategoryFilter {
+    fn from(category: Category) -> CategoryFilter {
+        CategoryFilter::Only(category)
+    }
+}
+
+/// Error returned when parsing a category that does not exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCategory(pub String);
+
+impl fmt::Display for UnknownCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown category: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownCategory {}
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use crate::details::*;
+use crate::scraping::*;
+use crate::types::*;
+
+/// Struct that represents which fields of Nyaa.si's pages can still be
+/// scraped, as found by [`NyaaClient::self_check`] or by
+/// [`check_search_page`] and [`check_view_page`] on saved pages.
+///
+/// Everything this library knows about Nyaa.si is scraped so a change in its
+/// markup shows up here first: a field that is no longer found is reported as
+/// missing and a value that is found but not understood (e.g. a new category
+/// or size unit) is reported as it is.
+///
+/// [`NyaaClient::self_check`]: ./struct.NyaaClient.html#method.self_check
+/// [`check_search_page`]: ./fn.check_search_page.html
+/// [`check_view_page`]: ./fn.check_view_page.html
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SelfCheck {
+    /// The fields that were checked, page by page.
+    pub fields: Vec<FieldCheck>,
+}
+
+impl SelfCheck {
+    /// Whether every field was found and understood.
+    pub fn is_ok(&self) -> bool {
+        self.fields.iter().all(FieldCheck::is_ok)
+    }
+
+    /// Iterate over the fields that were missing or not understood.
+    pub fn failures(&self) -> impl Iterator<Item = &FieldCheck> {
+        self.fields.iter().filter(|f| !f.is_ok())
+    }
+
+    /// Get the check of a field of a kind of page.
+    pub fn field(&self, page: PageKind, field: &str) -> Option<&FieldCheck> {
+        self.fields
+            .iter()
+            .find(|f| f.page == page && f.field == field)
+    }
+}
+
+impl fmt::Display for SelfCheck {
+    /// One line per field, e.g. `size of a search page: 2 unknown values
+    /// ("1.4 XiB", "350.2 XiB")`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for field in self.fields.iter() {
+            writeln!(f, "{}", field)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Struct that represents the check of a field (e.g. the size of the
+/// torrents) on a kind of page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldCheck {
+    /// Kind of page the field is on.
+    pub page: PageKind,
+    /// Name of the field (e.g. `"size"` or `"magnet"`).
+    pub field: &'static str,
+    /// How many times the field was looked for: once per row on search
+    /// pages.
+    pub checked: usize,
+    /// How many times the field was not found.
+    pub missing: usize,
+    /// Values that were found but not understood, without duplicates.
+    pub unknown: Vec<String>,
+}
+
+impl FieldCheck {
+    /// Whether the field was always found and understood.
+    pub fn is_ok(&self) -> bool {
+        self.missing == 0 && self.unknown.is_empty()
+    }
+}
+
+impl fmt::Display for FieldCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} of {}: ", self.field, self.page)?;
+
+        if self.is_ok() {
+            return write!(f, "ok");
+        }
+        if self.missing > 0 {
+            write!(f, "missing {} times out of {}", self.missing, self.checked)?;
+        }
+        if !self.unknown.is_empty() {
+            if self.missing > 0 {
+                write!(f, ", ")?;
+            }
+            let values = self
+                .unknown
+                .iter()
+                .map(|v| format!("{:?}", v))
+                .collect::<Vec<_>>();
+            write!(
+                f,
+                "{} unknown values ({})",
+                self.unknown.len(),
+                values.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check which fields of a page of search results (e.g. the html of
+/// `https://nyaa.si/`) can be scraped.
+///
+/// The page should have results: on a page without them only the table is
+/// checked.
+pub fn check_search_page(html: &str) -> SelfCheck {
+    match read_document(&mut html.as_bytes()) {
+        Ok(dom) => SelfCheck {
+            fields: check_search_document(&dom.document),
+        },
+        Err(_) => SelfCheck {
+            fields: vec![missing_page(PageKind::Search, "table")],
+        },
+    }
+}
+
+/// Check which fields of a torrent's page (e.g. the html of
+/// `https://nyaa.si/view/644786`) can be scraped.
+pub fn check_view_page(html: &str) -> SelfCheck {
+    match read_document(&mut html.as_bytes()) {
+        Ok(dom) => SelfCheck {
+            fields: check_view_document(&dom.document),
+        },
+        Err(_) => SelfCheck {
+            fields: vec![missing_page(PageKind::View, "panel")],
+        },
+    }
+}
+
+// Check of a field that was looked for once and not found.
+fn missing_page(page: PageKind, field: &'static str) -> FieldCheck {
+    let mut tally = Tally::new(page);
+    tally.record(field, Found::Missing);
+
+    tally.into_fields().remove(0)
+}
+
+// Enum that encodes what was found looking for a field once.
+pub(crate) enum Found {
+    Parsed,
+    Missing,
+    Unknown(String),
+}
+
+// Struct that counts what was found looking for each field of a kind of page,
+// in the order the fields were first looked for.
+pub(crate) struct Tally {
+    page: PageKind,
+    fields: Vec<FieldCheck>,
+}
+
+impl Tally {
+    pub(crate) fn new(page: PageKind) -> Tally {
+        Tally {
+            page,
+            fields: vec![],
+        }
+    }
+
+    pub(crate) fn record(&mut self, field: &'static str, found: Found) {
+        let i = match self.fields.iter().position(|f| f.field == field) {
+            Some(i) => i,
+            None => {
+                self.fields.push(FieldCheck {
+                    page: self.page,
+                    field,
+                    checked: 0,
+                    missing: 0,
+                    unknown: vec![],
+                });
+                self.fields.len() - 1
+            }
+        };
+
+        let check = &mut self.fields[i];
+        check.checked += 1;
+        match found {
+            Found::Parsed => (),
+            Found::Missing => check.missing += 1,
+            Found::Unknown(value) => {
+                if !check.unknown.contains(&value) {
+                    check.unknown.push(value);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn into_fields(self) -> Vec<FieldCheck> {
+        self.fields
+    }
+}
+
+// Id of the first torrent of a page of search results, whose page the
+// self-check looks at next.
+pub(crate) fn first_torrent_id(html: &[u8]) -> Option<u32> {
+    read_search_page_with(&mut &html[..], ParseMode::Lenient)
+        .ok()?
+        .torrents
+        .first()
+        .map(|t| t.id)
+}
@@ -0,0 +1,306 @@
+extern crate reqwest;
+
+use crate::builder::*;
+use crate::check::*;
+use crate::details::*;
+use crate::metainfo::*;
+use crate::pages::*;
+use crate::query::*;
+use crate::retry::*;
+use crate::rss::*;
+use crate::scraping::*;
+use crate::site::*;
+use crate::types::*;
+
+/// Search Nyaa.si for torrents by name.
+///
+/// This is a shorthand for [`search`] with a default [`SearchQuery`]. While
+/// using this function be sure to check for the Error (read [module level
+/// documentation] for more).
+///
+/// [`search`]: ./fn.search.html
+/// [`SearchQuery`]: ./struct.SearchQuery.html
+/// [module level documentation]: ./index.html
+pub fn torrent_search(s: &str) -> Result<Vec<Torrent>, Error> {
+    NyaaClient::builder().build()?.torrent_search(s)
+}
+
+/// Search Nyaa.si for torrents matching a [`SearchQuery`].
+///
+/// This is a shorthand for [`NyaaClient::search`] with a default client.
+/// While using this function be sure to check for the Error (read [module
+/// level documentation] for more).
+///
+/// [`SearchQuery`]: ./struct.SearchQuery.html
+/// [`NyaaClient::search`]: ./struct.NyaaClient.html#method.search
+/// [module level documentation]: ./index.html
+pub fn search(query: &SearchQuery) -> Result<Vec<Torrent>, Error> {
+    NyaaClient::builder().build()?.search(query)
+}
+
+/// Search Nyaa.si's RSS feed for torrents matching a [`SearchQuery`].
+///
+/// This is a shorthand for [`NyaaClient::search_rss`] with a default client.
+///
+/// [`SearchQuery`]: ./struct.SearchQuery.html
+/// [`NyaaClient::search_rss`]: ./struct.NyaaClient.html#method.search_rss
+pub fn search_rss(query: &SearchQuery) -> Result<Vec<Torrent>, Error> {
+    NyaaClient::builder().build()?.search_rss(query)
+}
+
+/// Get all the information Nyaa.si has about the torrent with the given id
+/// (i.e. scrape the page at `https://nyaa.si/view/<id>`).
+///
+/// This is a shorthand for [`NyaaClient::torrent_details`] with a default
+/// client. While using this function be sure to check for the Error (read
+/// [module level documentation] for more).
+///
+/// [`NyaaClient::torrent_details`]: ./struct.NyaaClient.html#method.torrent_details
+/// [module level documentation]: ./index.html
+pub fn torrent_details(id: u32) -> Result<TorrentDetails, Error> {
+    NyaaClient::builder().build()?.torrent_details(id)
+}
+
+/// Check that Nyaa.si's pages can still be scraped.
+///
+/// This is a shorthand for [`NyaaClient::self_check`] with a default client.
+///
+/// [`NyaaClient::self_check`]: ./struct.NyaaClient.html#method.self_check
+pub fn self_check() -> Result<SelfCheck, Error> {
+    NyaaClient::builder().build()?.self_check()
+}
+
+/// Client used to talk to Nyaa.si (or to any other site running the same
+/// software, e.g. a mirror).
+///
+/// A client can be reused for as many requests as you want: it keeps its
+/// connections open between requests.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// let client = nyaadesu::NyaaClient::builder()
+///     .base_url("https://nyaa.si")
+///     .timeout(Duration::from_secs(10))
+///     .build()
+///     .unwrap();
+/// let torrents = client.torrent_search("madoka");
+/// ```
+#[derive(Debug, Clone)]
+pub struct NyaaClient {
+    pub(crate) base_url: String,
+    pub(crate) site: Site,
+    pub(crate) parse_mode: ParseMode,
+    pub(crate) client: reqwest::blocking::Client,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+}
+
+impl NyaaClient {
+    /// Create a client for Nyaa.si with the default configuration.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the underlying HTTP client can't be initialized (e.g. if
+    /// no TLS backend is available). Use [`NyaaClient::builder`] to handle the
+    /// error instead.
+    ///
+    /// [`NyaaClient::builder`]: #method.builder
+    pub fn new() -> NyaaClient {
+        NyaaClient::builder()
+            .build()
+            .expect("could not initialize the HTTP client")
+    }
+
+    /// Create a builder to configure a client.
+    pub fn builder() -> NyaaClientBuilder {
+        NyaaClientBuilder::new()
+    }
+
+    /// Url of the site the client talks to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Site the client talks to (or to a mirror of).
+    pub fn site(&self) -> Site {
+        self.site
+    }
+
+    /// Search for torrents by name.
+    pub fn torrent_search(&self, s: &str) -> Result<Vec<Torrent>, Error> {
+        self.search(&SearchQuery::new(s))
+    }
+
+    /// Search for torrents matching a [`SearchQuery`], fetching every page of
+    /// results (or as many as the query's limits allow).
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<Torrent>, Error> {
+        self.search_results(query).collect()
+    }
+
+    /// Iterate over the pages of results of a [`SearchQuery`], fetching them
+    /// only when they are needed.
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    pub fn search_pages(&self, query: &SearchQuery) -> SearchPages<'_> {
+        SearchPages::new(self, query)
+    }
+
+    /// Iterate over the torrents matching a [`SearchQuery`], fetching pages
+    /// of results only when they are needed.
+    ///
+    /// ```no_run
+    /// let client = nyaadesu::NyaaClient::new();
+    /// let query = nyaadesu::SearchQuery::new("1080p");
+    ///
+    /// // Only the first page of results is downloaded.
+    /// for torrent in client.search_results(&query).take(20) {
+    ///     println!("{}", torrent.unwrap().name);
+    /// }
+    /// ```
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    pub fn search_results(&self, query: &SearchQuery) -> SearchResults<'_> {
+        SearchResults::new(self, query)
+    }
+
+    /// Get the n-th page of results (starting from 1) of a [`SearchQuery`].
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    pub fn search_page(&self, query: &SearchQuery, n: u32) -> Result<SearchPage, Error> {
+        let mut page = self.send(
+            self.client
+                .get(format!("{}/", self.base_url))
+                .query(&query.params(n)),
+        )?;
+
+        let mut search_page = read_search_page_with(&mut page, self.parse_mode)?;
+        for torrent in search_page.torrents.iter_mut() {
+            torrent.resolve_links(&self.base_url);
+        }
+
+        Ok(search_page)
+    }
+
+    /// Search for torrents matching a [`SearchQuery`] using the RSS feed
+    /// instead of the html pages.
+    ///
+    /// The feed is cheaper to fetch and less likely to break than the html
+    /// pages but it only holds the newest results (the 75 most recent on
+    /// Nyaa.si) and it ignores the query's sorting.
+    ///
+    /// [`SearchQuery`]: ./struct.SearchQuery.html
+    pub fn search_rss(&self, query: &SearchQuery) -> Result<Vec<Torrent>, Error> {
+        let mut feed = self.send(
+            self.client
+                .get(format!("{}/", self.base_url))
+                .query(&query.rss_params()),
+        )?;
+
+        read_rss_from(&mut feed, self.site)
+    }
+
+    /// Get all the information available about the torrent with the given id.
+    pub fn torrent_details(&self, id: u32) -> Result<TorrentDetails, Error> {
+        let mut page = self.send(self.client.get(format!("{}/view/{}", self.base_url, id)))?;
+
+        let mut details = read_view_page(&mut page)?;
+        details.torrent.resolve_links(&self.base_url);
+
+        Ok(details)
+    }
+
+    /// Download the .torrent file of the torrent with the given id.
+    pub fn download_torrent(&self, id: u32) -> Result<Vec<u8>, Error> {
+        let mut file = self.send(
+            self.client
+                .get(format!("{}/download/{}.torrent", self.base_url, id)),
+        )?;
+
+        let mut bytes = vec![];
+        std::io::Read::read_to_end(&mut file, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Download and decode the .torrent file of the torrent with the given
+    /// id.
+    pub fn torrent_metainfo(&self, id: u32) -> Result<Metainfo, Error> {
+        parse_torrent_file(&self.download_torrent(id)?)
+    }
+
+    /// Check that the site's pages can still be scraped: fetch the first page
+    /// of the site (every torrent, newest first) and the page of its first
+    /// torrent and report which fields of them parse (see [`SelfCheck`]).
+    ///
+    /// Pages that can't be scraped are not errors: they are what the report is
+    /// about. This only fails if a page can't be fetched.
+    ///
+    /// ```no_run
+    /// let check = nyaadesu::NyaaClient::new().self_check().unwrap();
+    /// if !check.is_ok() {
+    ///     eprint!("Nyaa.si changed:\n{}", check);
+    /// }
+    /// ```
+    ///
+    /// [`SelfCheck`]: ./struct.SelfCheck.html
+    pub fn self_check(&self) -> Result<SelfCheck, Error> {
+        let page = self
+            .send(self.client.get(format!("{}/", self.base_url)))?
+            .bytes()?;
+
+        let mut check = check_search_page(&String::from_utf8_lossy(&page));
+        if let Some(id) = first_torrent_id(&page) {
+            let view = self
+                .send(self.client.get(format!("{}/view/{}", self.base_url, id)))?
+                .text()?;
+            check.fields.extend(check_view_page(&view).fields);
+        }
+
+        Ok(check)
+    }
+
+    // Send a request, waiting for the rate limit and trying it again as the
+    // retry policy says. Error statuses are errors.
+    fn send(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, Error> {
+        let mut retries = 0;
+        loop {
+            if let Some(limiter) = &self.rate_limiter {
+                std::thread::sleep(limiter.reserve());
+            }
+
+            let request = request
+                .try_clone()
+                // Only requests with a streamed body can't be cloned.
+                .ok_or_else(|| {
+                    Error::Configuration(
+                        "requests with a streamed body can't be retried".to_string(),
+                    )
+                })?;
+            let (error, retry_after) = match request.send() {
+                Ok(response) => match response.error_for_status_ref() {
+                    Ok(_) => return Ok(response),
+                    Err(e) => (Error::from(e), retry_after(response.headers())),
+                },
+                Err(e) => (Error::from(e), None),
+            };
+
+            match self.retry.delay(retries, &error, retry_after) {
+                Some(delay) => std::thread::sleep(delay),
+                None => return Err(error),
+            }
+            retries += 1;
+        }
+    }
+}
+
+impl Default for NyaaClient {
+    fn default() -> NyaaClient {
+        NyaaClient::new()
+    }
+}
@@ -0,0 +1,391 @@
+extern crate html5ever;
+
+use crate::category::*;
+use crate::check::*;
+use crate::magnet::*;
+use crate::scraping::*;
+use crate::size::*;
+use crate::timestamp::*;
+use crate::types::*;
+
+/// Parse a torrent's page (e.g. the html of `https://nyaa.si/view/644786`)
+/// that was already downloaded.
+///
+/// This does not do any network request so you can use it with your own HTTP
+/// client or with saved pages.
+pub fn parse_view_page(html: &str) -> Result<TorrentDetails, Error> {
+    read_view_page(&mut html.as_bytes())
+}
+
+/// Like [`parse_view_page`] but read the html from a reader.
+///
+/// [`parse_view_page`]: ./fn.parse_view_page.html
+pub fn read_view_page<R: std::io::Read>(r: &mut R) -> Result<TorrentDetails, Error> {
+    let dom = read_document(r)?;
+    let details = parse_details(&dom.document)?;
+
+    Ok(details)
+}
+
+// Error for a field of the torrent's page that could not be scraped.
+fn missing(field: &'static str) -> ScrapingError {
+    ScrapingError::new(PageKind::View, None, field)
+}
+
+// Error for a field of the torrent's page whose value could not be parsed.
+fn unknown(field: &'static str, value: String) -> ScrapingError {
+    missing(field).with_value(Some(value))
+}
+
+// Parse a torrent's page.
+fn parse_details(handle: &html5ever::rcdom::Handle) -> Result<TorrentDetails, ScrapingError> {
+    // The first panel holds the torrent's information, the color of the panel
+    // tells if it is trusted or a remake.
+    let panel = find(handle, &|n| is_tag(n, "div") && has_class(n, "panel"))
+        .ok_or_else(|| missing("panel"))?;
+    let trusted = has_class(&panel, "panel-success");
+    let remake = has_class(&panel, "panel-danger");
+
+    let title = find(&panel, &|n| has_class(n, "panel-title")).ok_or_else(|| missing("name"))?;
+    let name = text_content(&title).trim().to_string();
+
+    let body = find(&panel, &|n| has_class(n, "panel-body")).ok_or_else(|| missing("panel"))?;
+    let fields = get_fields(&body);
+    let field = |label: &str, field: &'static str| {
+        fields
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| missing(field))
+    };
+
+    let category = field("Category:", "category")?;
+    let category =
+        get_category(&category).ok_or_else(|| unknown("category", get_category_name(&category)))?;
+    let date = field("Date:", "date")?;
+    let date = get_timestamp(&date)
+        .ok_or_else(|| missing("date").with_value(get_attribute(&date, "data-timestamp")))?;
+    let submitter = find(&field("Submitter:", "submitter")?, &|n| is_tag(n, "a"))
+        .map(|a| text_content(&a).trim().to_string());
+    let information = find(&field("Information:", "information")?, &|n| is_tag(n, "a"))
+        .and_then(|a| get_attribute(&a, "href"));
+    let number = |label: &str, name: &'static str| {
+        let text = get_field_text(&field(label, name)?);
+        text.parse::<u32>().map_err(|_| unknown(name, text))
+    };
+    let seeders = number("Seeders:", "seeders")?;
+    let leechers = number("Leechers:", "leechers")?;
+    let completed_downloads = number("Completed:", "completed downloads")?;
+    let size = get_field_text(&field("File size:", "size")?);
+    let size = ByteSize::parse(&size).ok_or_else(|| unknown("size", size))?;
+    let info_hash = get_field_text(&field("Info hash:", "info hash")?);
+    let info_hash = info_hash
+        .parse::<InfoHash>()
+        .map_err(|_| unknown("info hash", info_hash))?;
+
+    // Links are in the footer of the panel.
+    let footer = find(&panel, &|n| has_class(n, "panel-footer")).ok_or_else(|| missing("links"))?;
+    let mut links = TorrentLinks::default();
+    for href in find_all(&footer, &|n| is_tag(n, "a"))
+        .iter()
+        .filter_map(|a| get_attribute(a, "href"))
+    {
+        if href.starts_with("/download") {
+            links.download_url = Some(href);
+        } else if href.starts_with("magnet") {
+            links.magnet = Some(href);
+        }
+    }
+
+    // The id of the torrent is the name of the .torrent file.
+    let id = links
+        .download_url
+        .as_ref()
+        .and_then(|link| {
+            link.trim_start_matches("/download/")
+                .trim_end_matches(".torrent")
+                .parse::<u32>()
+                .ok()
+        })
+        .ok_or_else(|| missing("id"))?;
+    links.view_url = Some(format!("/view/{}", id));
+
+    let description = find(handle, &|n| {
+        get_attribute(n, "id").as_deref() == Some("torrent-description")
+    })
+    .map(|d| text_content(&d))
+    .unwrap_or_default();
+
+    // Some torrents (e.g. very old ones) have no file list.
+    let files = match find(handle, &|n| has_class(n, "torrent-file-list")) {
+        Some(list) => match find(&list, &|n| is_tag(n, "ul")) {
+            Some(ul) => get_files(&ul).ok_or_else(|| missing("files"))?,
+            None => vec![],
+        },
+        None => vec![],
+    };
+
+    let comments = find_all(handle, &|n| has_class(n, "comment-panel"))
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            get_comment(c).ok_or_else(|| ScrapingError::new(PageKind::View, Some(i), "comment"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TorrentDetails {
+        torrent: Torrent {
+            id,
+            category,
+            name,
+            links,
+            size,
+            date,
+            seeders,
+            leechers,
+            completed_downloads,
+            comments: comments.len() as u32,
+            status: Status::from_flags(trusted, remake),
+        },
+        submitter,
+        information,
+        info_hash,
+        description,
+        files,
+        comments,
+    })
+}
+
+// Check which fields of a torrent's page can be scraped.
+pub(crate) fn check_view_document(handle: &html5ever::rcdom::Handle) -> Vec<FieldCheck> {
+    let mut tally = Tally::new(PageKind::View);
+
+    let panel = find(handle, &|n| is_tag(n, "div") && has_class(n, "panel"));
+    tally.record("panel", found(panel.as_ref()));
+    let panel = match panel {
+        Some(panel) => panel,
+        None => return tally.into_fields(),
+    };
+
+    let title = find(&panel, &|n| has_class(n, "panel-title"));
+    tally.record(
+        "name",
+        found(title.filter(|t| !text_content(t).trim().is_empty())),
+    );
+
+    let fields = find(&panel, &|n| has_class(n, "panel-body"))
+        .map(|body| get_fields(&body))
+        .unwrap_or_default();
+    let field = |label: &str| {
+        fields
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, value)| value.clone())
+    };
+
+    tally.record(
+        "category",
+        match field("Category:") {
+            Some(category) => {
+                let name = get_category_name(&category);
+                if name.parse::<Category>().is_ok() {
+                    Found::Parsed
+                } else {
+                    Found::Unknown(name)
+                }
+            }
+            None => Found::Missing,
+        },
+    );
+    tally.record(
+        "date",
+        match field("Date:").and_then(|d| get_attribute(&d, "data-timestamp")) {
+            Some(date) if date.parse::<u64>().is_ok() => Found::Parsed,
+            Some(date) => Found::Unknown(date),
+            None => Found::Missing,
+        },
+    );
+    tally.record("submitter", found(field("Submitter:")));
+    tally.record("information", found(field("Information:")));
+    for (label, name) in [
+        ("Seeders:", "seeders"),
+        ("Leechers:", "leechers"),
+        ("Completed:", "completed downloads"),
+    ]
+    .iter()
+    {
+        tally.record(
+            name,
+            parsed_text(field(label).as_ref(), |n| n.parse::<u32>().ok()),
+        );
+    }
+    tally.record(
+        "size",
+        parsed_text(field("File size:").as_ref(), ByteSize::parse),
+    );
+    tally.record(
+        "info hash",
+        parsed_text(field("Info hash:").as_ref(), |h| h.parse::<InfoHash>().ok()),
+    );
+
+    let links = find(&panel, &|n| has_class(n, "panel-footer"))
+        .map(|footer| find_all(&footer, &|n| is_tag(n, "a")))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|a| get_attribute(a, "href"))
+        .collect::<Vec<_>>();
+    tally.record(
+        "id",
+        match links.iter().find(|l| l.starts_with("/download")) {
+            Some(link) => {
+                let id = link
+                    .trim_start_matches("/download/")
+                    .trim_end_matches(".torrent");
+                if id.parse::<u32>().is_ok() {
+                    Found::Parsed
+                } else {
+                    Found::Unknown(link.clone())
+                }
+            }
+            None => Found::Missing,
+        },
+    );
+    tally.record(
+        "magnet",
+        match links.iter().find(|l| l.starts_with("magnet")) {
+            Some(magnet) if magnet.parse::<Magnet>().is_ok() => Found::Parsed,
+            Some(magnet) => Found::Unknown(magnet.clone()),
+            None => Found::Missing,
+        },
+    );
+
+    tally.record(
+        "description",
+        found(find(handle, &|n| {
+            get_attribute(n, "id").as_deref() == Some("torrent-description")
+        })),
+    );
+    let files = find(handle, &|n| has_class(n, "torrent-file-list"))
+        .and_then(|list| find(&list, &|n| is_tag(n, "ul")))
+        .and_then(|ul| get_files(&ul));
+    tally.record("files", found(files));
+    for comment in find_all(handle, &|n| has_class(n, "comment-panel")) {
+        tally.record("comment", found(get_comment(&comment)));
+    }
+
+    tally.into_fields()
+}
+
+// Get the "label: value" pairs of the information panel. Each row of the panel
+// alternates label cells and value cells.
+fn get_fields(body: &html5ever::rcdom::Handle) -> Vec<(String, html5ever::rcdom::Handle)> {
+    let mut fields = vec![];
+
+    for row in find_all(body, &|n| has_class(n, "row")) {
+        let cells = element_children(&row);
+        for pair in cells.chunks(2) {
+            if let [label, value] = pair {
+                fields.push((text_content(label).trim().to_string(), value.clone()));
+            }
+        }
+    }
+
+    fields
+}
+
+// Extract the category from the category field: its text is the category's
+// name (e.g. "Anime - English-translated") and the last link points to the
+// category's search page (e.g. "/?c=1_2").
+fn get_category(field: &html5ever::rcdom::Handle) -> Option<Category> {
+    if let Ok(category) = get_category_name(field).parse() {
+        return Some(category);
+    }
+
+    let links = find_all(field, &|n| is_tag(n, "a"));
+    let href = get_attribute(links.last()?, "href")?;
+
+    Category::from_code(href.trim_start_matches("/?c="))
+}
+
+// Extract the name of the category from the category field, on one line.
+fn get_category_name(field: &html5ever::rcdom::Handle) -> String {
+    get_field_text(field)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Extract the trimmed text of a field.
+fn get_field_text(field: &html5ever::rcdom::Handle) -> String {
+    text_content(field).trim().to_string()
+}
+
+// Extract the unix timestamp stored in a node's "data-timestamp" attribute.
+fn get_timestamp(node: &html5ever::rcdom::Handle) -> Option<Timestamp> {
+    get_attribute(node, "data-timestamp")?
+        .parse::<u64>()
+        .ok()
+        .map(Timestamp::from_unix)
+}
+
+// Extract the entries of a file list (i.e. an <ul>).
+fn get_files(ul: &html5ever::rcdom::Handle) -> Option<Vec<FileEntry>> {
+    let mut entries = vec![];
+
+    for li in element_children(ul).iter().filter(|n| is_tag(n, "li")) {
+        let children = element_children(li);
+
+        // Folders are a link followed by the list of their content.
+        let folder = children.iter().find(|n| has_class(n, "folder"));
+        if let Some(folder) = folder {
+            let name = text_content(folder).trim().to_string();
+            let entries_in_folder = match children.iter().find(|n| is_tag(n, "ul")) {
+                Some(ul) => get_files(ul)?,
+                None => vec![],
+            };
+            entries.push(FileEntry::Folder {
+                name,
+                entries: entries_in_folder,
+            });
+            continue;
+        }
+
+        // Files are their name followed by their size between parentheses.
+        let size_node = children.iter().find(|n| has_class(n, "file-size"))?;
+        let size_raw = text_content(size_node);
+        let size = ByteSize::parse(
+            size_raw
+                .trim()
+                .trim_start_matches('(')
+                .trim_end_matches(')'),
+        )?;
+
+        let mut name = String::new();
+        for child in li.children.borrow().iter() {
+            if let html5ever::rcdom::NodeData::Text { contents } = &child.data {
+                name.push_str(&contents.borrow());
+            }
+        }
+
+        entries.push(FileEntry::File {
+            name: name.trim().to_string(),
+            size,
+        });
+    }
+
+    Some(entries)
+}
+
+// Extract a comment from its panel.
+fn get_comment(panel: &html5ever::rcdom::Handle) -> Option<Comment> {
+    let user = find(panel, &|n| has_class(n, "col-md-2"))?;
+    let author = get_field_text(&find(&user, &|n| is_tag(n, "a"))?);
+
+    let date_node = find(panel, &|n| get_attribute(n, "data-timestamp").is_some())?;
+    let date = get_timestamp(&date_node)?;
+
+    let content = find(panel, &|n| has_class(n, "comment-content"))?;
+    let text = text_content(&content);
+
+    Some(Comment { author, date, text })
+}
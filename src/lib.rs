@@ -4,19 +4,106 @@
 //! every information that this library can provide is obtained by scraping
 //! HTML. This means that if something changes in [Nyaa.si] there is a
 //! possibility for `nyaadesu` to stop functioning so be sure to always check
-//! for an [`Error`] while using the [`search_torrent`] function (and maybe
-//! report it on [GitHub]).
+//! for an [`Error`] while searching with [`NyaaClient::search`] (and maybe
+//! report it on [GitHub]). [`self_check`] reports which fields of the pages
+//! can still be scraped, naming any value (e.g. a category or a size unit) it
+//! does not understand.
 //!
 //! See [examples] for a basic understanding of how to use this library.
 //!
+//! # Serde
+//!
+//! With the `serde` feature the public types implement `Serialize` and
+//! `Deserialize` with a stable JSON shape:
+//!
+//! - structs are objects with their fields' names and enums without data are
+//!   snake case strings (e.g. `"trusted"` or `"no_remakes"`);
+//! - categories and category filters are their Nyaa.si codes (e.g. `"1_2"`).
+//!   Sukebei's codes are prefixed with `sukebei:` (e.g. `"sukebei:1_4"`) since
+//!   they are the same as Nyaa.si's;
+//! - sizes are numbers of bytes and dates are unix timestamps in seconds;
+//! - info hashes are lowercase hex strings and magnet links are URIs;
+//! - the variants of [`FileEntry`] and [`Layout`] are tagged with a `type`
+//!   field;
+//! - errors are only serialized, as their `kind` and `message` (and the HTTP
+//!   `status` of status errors), and so are self-check reports.
+//!
+//! A torrent looks like this:
+//!
+//! ```json
+//! {
+//!   "id": 1300001,
+//!   "category": "1_2",
+//!   "name": "[SubsPlease] Little Witch Academia - 01 (1080p)",
+//!   "links": {
+//!     "download_url": "/download/1300001.torrent",
+//!     "magnet": "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567",
+//!     "view_url": "/view/1300001"
+//!   },
+//!   "size": 1503238554,
+//!   "date": 1600000000,
+//!   "seeders": 123,
+//!   "leechers": 4,
+//!   "completed_downloads": 5678,
+//!   "comments": 3,
+//!   "status": "trusted"
+//! }
+//! ```
+//!
 //! [Nyaa.si]: https://nyaa.si
 //! [examples]: https://github.com/gRastello/nyaadesu/tree/master/examples
 //! [`Error`]: ./enum.Error.html
-//! [`search_torrent`]: ./fn.torrent_search.html
+//! [`NyaaClient::search`]: ./struct.NyaaClient.html#method.search
+//! [`FileEntry`]: ./enum.FileEntry.html
+//! [`Layout`]: ./enum.Layout.html
+//! [`self_check`]: ./fn.self_check.html
 //! [GitHub]: https://github.com/grastello/nyaadesu
 
-mod types;
+// Without a client only the parsers are left and the request helpers go unused.
+#![cfg_attr(not(any(feature = "blocking", feature = "async")), allow(dead_code))]
+
+#[cfg(feature = "async")]
+mod async_client;
+mod builder;
+mod category;
+mod check;
+#[cfg(feature = "blocking")]
+mod client;
+mod details;
+mod magnet;
+mod metainfo;
+#[cfg(feature = "blocking")]
+mod pages;
+mod query;
+mod retry;
+mod rss;
 mod scraping;
+#[cfg(feature = "serde")]
+mod serialization;
+mod site;
+mod size;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timestamp;
+mod types;
 
-pub use self::types::*;
+#[cfg(feature = "async")]
+pub use self::async_client::*;
+pub use self::builder::*;
+pub use self::category::*;
+pub use self::check::*;
+#[cfg(feature = "blocking")]
+pub use self::client::*;
+pub use self::details::*;
+pub use self::magnet::*;
+pub use self::metainfo::*;
+#[cfg(feature = "blocking")]
+pub use self::pages::*;
+pub use self::query::*;
+pub use self::retry::*;
+pub use self::rss::*;
 pub use self::scraping::*;
+pub use self::site::*;
+pub use self::size::*;
+pub use self::timestamp::*;
+pub use self::types::*;
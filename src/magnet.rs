@@ -0,0 +1,286 @@
+extern crate percent_encoding;
+
+use std::fmt;
+use std::str::FromStr;
+
+// Characters that are percent-encoded in magnet links' parameters.
+const COMPONENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// Alphabet of base32 (RFC 4648) encoded info hashes.
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Struct that represents a magnet link of a BitTorrent v1 torrent.
+///
+/// It can be parsed from a `magnet:` URI and serialized back to one:
+///
+/// ```
+/// use nyaadesu::Magnet;
+///
+/// let magnet: Magnet = "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&dn=Little%20Witch"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(magnet.info_hash.to_hex(), "0123456789abcdef0123456789abcdef01234567");
+/// assert_eq!(magnet.name.as_deref(), Some("Little Witch"));
+/// ```
+///
+/// Serialization is canonical: the info hash is written in lowercase hex and
+/// the parameters always come in the same order (`xt`, `dn`, `xl`, `tr`, `ws`)
+/// so two links to the same torrent with the same trackers are equal once
+/// parsed and printed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Magnet {
+    /// Info hash of the torrent (the `xt` parameter).
+    pub info_hash: InfoHash,
+    /// Display name of the torrent (the `dn` parameter).
+    pub name: Option<String>,
+    /// Total size of the torrent's files in bytes (the `xl` parameter).
+    pub length: Option<u64>,
+    /// Urls of the trackers (the `tr` parameters).
+    pub trackers: Vec<String>,
+    /// Urls of the web seeds (the `ws` parameters).
+    pub web_seeds: Vec<String>,
+}
+
+impl Magnet {
+    /// Create a magnet link with only an info hash.
+    pub fn new(info_hash: InfoHash) -> Magnet {
+        Magnet {
+            info_hash,
+            name: None,
+            length: None,
+            trackers: vec![],
+            web_seeds: vec![],
+        }
+    }
+
+    /// Parse a `magnet:` URI. Unknown parameters are ignored and only the
+    /// first BitTorrent info hash is kept.
+    pub fn parse(uri: &str) -> Result<Magnet, MagnetError> {
+        let params = match uri.get(..8) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("magnet:?") => &uri[8..],
+            _ => return Err(MagnetError::NotAMagnet),
+        };
+
+        let mut info_hash = None;
+        let mut magnet = Magnet::new(InfoHash([0; 20]));
+
+        for param in params.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = match param.find('=') {
+                Some(i) => (&param[..i], &param[i + 1..]),
+                None => (param, ""),
+            };
+            // Parameters may be numbered when repeated (e.g. "tr.1").
+            let key = key.split('.').next().unwrap_or(key);
+
+            match key {
+                "xt" if info_hash.is_none() => {
+                    let value = decode(value)?;
+                    if let Some(hash) = strip_prefix_ignore_case(&value, "urn:btih:") {
+                        info_hash = Some(hash.parse()?);
+                    }
+                }
+                "dn" => magnet.name = Some(decode(&value.replace('+', " "))?),
+                "xl" => {
+                    let length = decode(value)?
+                        .parse()
+                        .map_err(|_| MagnetError::InvalidLength)?;
+                    magnet.length = Some(length);
+                }
+                "tr" => magnet.trackers.push(decode(value)?),
+                "ws" => magnet.web_seeds.push(decode(value)?),
+                _ => (),
+            }
+        }
+
+        magnet.info_hash = info_hash.ok_or(MagnetError::MissingInfoHash)?;
+        Ok(magnet)
+    }
+
+    /// Serialize the magnet link to a canonical `magnet:` URI.
+    pub fn to_uri(&self) -> String {
+        let mut uri = format!("magnet:?xt=urn:btih:{}", self.info_hash.to_hex());
+
+        if let Some(name) = &self.name {
+            uri.push_str("&dn=");
+            uri.push_str(&encode(name));
+        }
+        if let Some(length) = self.length {
+            uri.push_str(&format!("&xl={}", length));
+        }
+        for tracker in self.trackers.iter() {
+            uri.push_str("&tr=");
+            uri.push_str(&encode(tracker));
+        }
+        for web_seed in self.web_seeds.iter() {
+            uri.push_str("&ws=");
+            uri.push_str(&encode(web_seed));
+        }
+
+        uri
+    }
+}
+
+impl fmt::Display for Magnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_uri())
+    }
+}
+
+impl FromStr for Magnet {
+    type Err = MagnetError;
+
+    fn from_str(s: &str) -> Result<Magnet, MagnetError> {
+        Magnet::parse(s)
+    }
+}
+
+/// Struct that represents the 20 bytes (SHA-1) info hash of a BitTorrent v1
+/// torrent.
+///
+/// It is parsed from either its hex (40 characters) or its base32 (32
+/// characters) form and it is displayed in lowercase hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InfoHash([u8; 20]);
+
+impl InfoHash {
+    /// Create an info hash from its bytes.
+    pub fn from_bytes(bytes: [u8; 20]) -> InfoHash {
+        InfoHash(bytes)
+    }
+
+    /// Bytes of the info hash.
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// Lowercase hex form of the info hash.
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Uppercase base32 form of the info hash.
+    pub fn to_base32(&self) -> String {
+        let mut encoded = String::with_capacity(32);
+        let mut bits = 0u32;
+        let mut n = 0;
+
+        for byte in self.0.iter() {
+            bits = (bits << 8) | u32::from(*byte);
+            n += 8;
+            while n >= 5 {
+                n -= 5;
+                encoded.push(BASE32[(bits >> n) as usize] as char);
+                bits &= (1 << n) - 1;
+            }
+        }
+
+        encoded
+    }
+}
+
+impl fmt::Display for InfoHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl FromStr for InfoHash {
+    type Err = MagnetError;
+
+    fn from_str(s: &str) -> Result<InfoHash, MagnetError> {
+        let bytes = match s.len() {
+            40 => decode_hex(s),
+            32 => decode_base32(s),
+            _ => None,
+        };
+
+        bytes.map(InfoHash).ok_or(MagnetError::InvalidInfoHash)
+    }
+}
+
+/// Enum that encodes the reasons a magnet link can't be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MagnetError {
+    /// The link does not start with `magnet:?`.
+    NotAMagnet,
+    /// The link has no BitTorrent info hash (`xt=urn:btih:...`).
+    MissingInfoHash,
+    /// The info hash is neither 40 hex nor 32 base32 characters.
+    InvalidInfoHash,
+    /// The exact length (`xl`) is not a number.
+    InvalidLength,
+    /// A parameter is not valid percent-encoded UTF-8.
+    InvalidEncoding,
+}
+
+impl fmt::Display for MagnetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MagnetError::NotAMagnet => write!(f, "not a magnet link"),
+            MagnetError::MissingInfoHash => write!(f, "magnet link has no info hash"),
+            MagnetError::InvalidInfoHash => write!(f, "invalid info hash in magnet link"),
+            MagnetError::InvalidLength => write!(f, "invalid exact length in magnet link"),
+            MagnetError::InvalidEncoding => write!(f, "invalid encoding in magnet link"),
+        }
+    }
+}
+
+impl std::error::Error for MagnetError {}
+
+// Percent-decode a parameter of a magnet link.
+fn decode(value: &str) -> Result<String, MagnetError> {
+    percent_encoding::percent_decode_str(value)
+        .decode_utf8()
+        .map(|v| v.into_owned())
+        .map_err(|_| MagnetError::InvalidEncoding)
+}
+
+// Percent-encode a parameter of a magnet link.
+fn encode(value: &str) -> String {
+    percent_encoding::utf8_percent_encode(value, COMPONENT).to_string()
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    match s.get(..prefix.len()) {
+        Some(start) if start.eq_ignore_ascii_case(prefix) => Some(&s[prefix.len()..]),
+        _ => None,
+    }
+}
+
+fn decode_hex(s: &str) -> Option<[u8; 20]> {
+    if !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut bytes = [0; 20];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(s.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+
+    Some(bytes)
+}
+
+fn decode_base32(s: &str) -> Option<[u8; 20]> {
+    let mut bytes = [0; 20];
+    let mut bits = 0u32;
+    let mut n = 0;
+    let mut i = 0;
+
+    for c in s.bytes() {
+        let value = BASE32.iter().position(|b| *b == c.to_ascii_uppercase())?;
+        bits = (bits << 5) | value as u32;
+        n += 5;
+        if n >= 8 {
+            n -= 8;
+            bytes[i] = (bits >> n) as u8;
+            bits &= (1 << n) - 1;
+            i += 1;
+        }
+    }
+
+    Some(bytes)
+}
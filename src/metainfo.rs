@@ -0,0 +1,349 @@
+extern crate sha1_smol;
+
+use crate::magnet::*;
+use crate::types::*;
+
+// How deep lists and dictionaries can be nested in a .torrent file.
+const MAX_DEPTH: usize = 64;
+
+/// Struct that represents the content (metainfo) of a .torrent file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metainfo {
+    /// Info hash (v1) of the torrent: the SHA-1 of the bencoded `info`
+    /// dictionary.
+    pub info_hash: InfoHash,
+    /// Name of the torrent: the name of the file or of the root folder.
+    pub name: String,
+    /// Number of bytes in each piece.
+    pub piece_length: u64,
+    /// SHA-1 hashes of the pieces.
+    pub pieces: Vec<[u8; 20]>,
+    /// Files of the torrent.
+    pub layout: Layout,
+    /// Url of the tracker.
+    pub announce: Option<String>,
+    /// Tiers of trackers (BEP 12).
+    pub announce_list: Vec<Vec<String>>,
+    /// Creation date of the torrent as unix timestamp.
+    pub creation_date: Option<u64>,
+    /// Free-form comment.
+    pub comment: Option<String>,
+    /// Program that created the torrent.
+    pub created_by: Option<String>,
+}
+
+impl Metainfo {
+    /// Total size of the torrent's files in bytes.
+    pub fn total_length(&self) -> u64 {
+        match &self.layout {
+            Layout::SingleFile { length } => *length,
+            Layout::MultiFile { files } => files.iter().map(|f| f.length).sum(),
+        }
+    }
+
+    /// Build a magnet link with the torrent's info hash, name, size and
+    /// trackers.
+    pub fn magnet(&self) -> Magnet {
+        let mut magnet = Magnet::new(self.info_hash);
+        magnet.name = Some(self.name.clone());
+        magnet.length = Some(self.total_length());
+
+        for tracker in self
+            .announce
+            .iter()
+            .chain(self.announce_list.iter().flatten())
+        {
+            if !magnet.trackers.contains(tracker) {
+                magnet.trackers.push(tracker.clone());
+            }
+        }
+
+        magnet
+    }
+}
+
+/// Enum that encodes how the files of a torrent are laid out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum Layout {
+    /// A single file called like the torrent.
+    SingleFile { length: u64 },
+    /// Many files in a folder called like the torrent.
+    MultiFile { files: Vec<MetainfoFile> },
+}
+
+/// Struct that represents a file of a multi-file torrent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetainfoFile {
+    /// Path of the file inside the torrent's folder, one component at a time.
+    pub path: Vec<String>,
+    /// Size of the file in bytes.
+    pub length: u64,
+}
+
+/// Decode a .torrent file that was already downloaded.
+pub fn parse_torrent_file(bytes: &[u8]) -> Result<Metainfo, Error> {
+    let missing = |field| ScrapingError::new(PageKind::TorrentFile, None, field);
+
+    let mut decoder = Decoder { bytes, pos: 0 };
+    let (torrent, info_bytes) = decoder.torrent().ok_or_else(|| missing("bencode"))?;
+    let info = torrent.get(b"info").ok_or_else(|| missing("info"))?;
+
+    let name = info
+        .get(b"name.utf-8")
+        .or_else(|| info.get(b"name"))
+        .and_then(Value::string)
+        .ok_or_else(|| missing("name"))?;
+    let piece_length = info
+        .get(b"piece length")
+        .and_then(Value::number)
+        .ok_or_else(|| missing("piece length"))?;
+    let pieces = info
+        .get(b"pieces")
+        .and_then(Value::bytes)
+        .filter(|p| p.len() % 20 == 0)
+        .ok_or_else(|| missing("pieces"))?
+        .chunks(20)
+        .map(|c| {
+            let mut piece = [0; 20];
+            piece.copy_from_slice(c);
+            piece
+        })
+        .collect();
+
+    let layout = match (info.get(b"length"), info.get(b"files")) {
+        (Some(length), None) => Layout::SingleFile {
+            length: length.number().ok_or_else(|| missing("length"))?,
+        },
+        (None, Some(files)) => Layout::MultiFile {
+            files: files
+                .list()
+                .ok_or_else(|| missing("files"))?
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    get_file(f)
+                        .ok_or_else(|| ScrapingError::new(PageKind::TorrentFile, Some(i), "file"))
+                })
+                .collect::<Result<_, _>>()?,
+        },
+        _ => return Err(missing("length").into()),
+    };
+
+    let announce_list = match torrent.get(b"announce-list") {
+        Some(tiers) => tiers
+            .list()
+            .and_then(|tiers| {
+                tiers
+                    .iter()
+                    .map(|tier| tier.list()?.iter().map(Value::string).collect())
+                    .collect()
+            })
+            .ok_or_else(|| missing("announce-list"))?,
+        None => vec![],
+    };
+
+    Ok(Metainfo {
+        info_hash: InfoHash::from_bytes(sha1_smol::Sha1::from(info_bytes).digest().bytes()),
+        name,
+        piece_length,
+        pieces,
+        layout,
+        announce: optional(&torrent, "announce", Value::string)?,
+        announce_list,
+        creation_date: optional(&torrent, "creation date", Value::number)?,
+        comment: optional(&torrent, "comment", Value::string)?,
+        created_by: optional(&torrent, "created by", Value::string)?,
+    })
+}
+
+/// Like [`parse_torrent_file`] but read the file from a reader.
+///
+/// [`parse_torrent_file`]: ./fn.parse_torrent_file.html
+pub fn read_torrent_file<R: std::io::Read>(r: &mut R) -> Result<Metainfo, Error> {
+    let mut bytes = vec![];
+    r.read_to_end(&mut bytes)?;
+
+    parse_torrent_file(&bytes)
+}
+
+// Get a file of a multi-file torrent from its dictionary.
+fn get_file(file: &Value) -> Option<MetainfoFile> {
+    let path = file
+        .get(b"path.utf-8")
+        .or_else(|| file.get(b"path"))?
+        .list()?
+        .iter()
+        .map(Value::string)
+        .collect::<Option<Vec<_>>>()?;
+    let length = file.get(b"length")?.number()?;
+
+    Some(MetainfoFile { path, length })
+}
+
+// Get an optional field of a dictionary, failing only if it has the wrong
+// type.
+fn optional<'a, T>(
+    dict: &Value<'a>,
+    field: &'static str,
+    convert: fn(&Value<'a>) -> Option<T>,
+) -> Result<Option<T>, ScrapingError> {
+    match dict.get(field.as_bytes()) {
+        Some(value) => convert(value)
+            .map(Some)
+            .ok_or_else(|| ScrapingError::new(PageKind::TorrentFile, None, field)),
+        None => Ok(None),
+    }
+}
+
+// A bencoded value.
+enum Value<'a> {
+    Integer(i64),
+    Bytes(&'a [u8]),
+    List(Vec<Value<'a>>),
+    Dictionary(Vec<(&'a [u8], Value<'a>)>),
+}
+
+impl<'a> Value<'a> {
+    fn get(&self, key: &[u8]) -> Option<&Value<'a>> {
+        match self {
+            Value::Dictionary(entries) => entries.iter().find(|(k, _)| *k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn number(&self) -> Option<u64> {
+        match self {
+            Value::Integer(i) if *i >= 0 => Some(*i as u64),
+            _ => None,
+        }
+    }
+
+    fn bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    // Strings that are not valid UTF-8 are decoded lossily.
+    fn string(&self) -> Option<String> {
+        self.bytes()
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    fn list(&self) -> Option<&[Value<'a>]> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+}
+
+// Bencode decoder.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    // Decode a whole .torrent file: a dictionary and the raw bytes of its
+    // `info` entry (the info hash is computed on those).
+    fn torrent(&mut self) -> Option<(Value<'a>, &'a [u8])> {
+        if self.next()? != b'd' {
+            return None;
+        }
+
+        let mut entries = vec![];
+        let mut info = None;
+        while self.peek()? != b'e' {
+            let key = self.string()?;
+            let start = self.pos;
+            let value = self.value(1)?;
+            if key == b"info" {
+                info = Some(&self.bytes[start..self.pos]);
+            }
+            entries.push((key, value));
+        }
+        self.pos += 1;
+
+        Some((Value::Dictionary(entries), info.unwrap_or(&[])))
+    }
+
+    fn value(&mut self, depth: usize) -> Option<Value<'a>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        match self.peek()? {
+            b'i' => {
+                self.pos += 1;
+                let end = self.find(b'e')?;
+                let integer = std::str::from_utf8(&self.bytes[self.pos..end])
+                    .ok()?
+                    .parse()
+                    .ok()?;
+                self.pos = end + 1;
+                Some(Value::Integer(integer))
+            }
+            b'l' => {
+                self.pos += 1;
+                let mut list = vec![];
+                while self.peek()? != b'e' {
+                    list.push(self.value(depth + 1)?);
+                }
+                self.pos += 1;
+                Some(Value::List(list))
+            }
+            b'd' => {
+                self.pos += 1;
+                let mut entries = vec![];
+                while self.peek()? != b'e' {
+                    let key = self.string()?;
+                    entries.push((key, self.value(depth + 1)?));
+                }
+                self.pos += 1;
+                Some(Value::Dictionary(entries))
+            }
+            b'0'..=b'9' => self.string().map(Value::Bytes),
+            _ => None,
+        }
+    }
+
+    // Decode a byte string ("<length>:<bytes>").
+    fn string(&mut self) -> Option<&'a [u8]> {
+        let colon = self.find(b':')?;
+        let length = std::str::from_utf8(&self.bytes[self.pos..colon])
+            .ok()?
+            .parse::<usize>()
+            .ok()?;
+        let start = colon + 1;
+        let end = start
+            .checked_add(length)
+            .filter(|e| *e <= self.bytes.len())?;
+        self.pos = end;
+
+        Some(&self.bytes[start..end])
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    // Position of the next occurrence of a byte.
+    fn find(&self, byte: u8) -> Option<usize> {
+        self.bytes[self.pos..]
+            .iter()
+            .position(|b| *b == byte)
+            .map(|i| self.pos + i)
+    }
+}
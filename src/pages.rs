@@ -0,0 +1,123 @@
+use crate::client::*;
+use crate::query::*;
+use crate::scraping::*;
+use crate::types::*;
+
+/// Iterator over the pages of results of a search. Pages are fetched only when
+/// they are needed.
+///
+/// It is created by [`NyaaClient::search_pages`]. It stops after the last page
+/// of results (as told by the pages' [`SearchMeta`], or at the first page
+/// without results), after the query's [`max_pages`] or after the first
+/// error.
+///
+/// [`NyaaClient::search_pages`]: ./struct.NyaaClient.html#method.search_pages
+/// [`SearchMeta`]: ./struct.SearchMeta.html
+/// [`max_pages`]: ./struct.SearchQuery.html#method.max_pages
+#[derive(Debug, Clone)]
+pub struct SearchPages<'a> {
+    client: &'a NyaaClient,
+    query: SearchQuery,
+    next: u32,
+    done: bool,
+}
+
+impl<'a> SearchPages<'a> {
+    pub(crate) fn new(client: &'a NyaaClient, query: &SearchQuery) -> SearchPages<'a> {
+        SearchPages {
+            client,
+            query: query.clone(),
+            next: 1,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SearchPages<'_> {
+    type Item = Result<SearchPage, Error>;
+
+    fn next(&mut self) -> Option<Result<SearchPage, Error>> {
+        if self.done {
+            return None;
+        }
+
+        if let Some(max_pages) = self.query.max_pages {
+            if self.next > max_pages {
+                self.done = true;
+                return None;
+            }
+        }
+
+        match self.client.search_page(&self.query, self.next) {
+            Ok(page) => {
+                if page.state != Page::Torrents {
+                    self.done = true;
+                    return None;
+                }
+
+                // Pages that tell how many pages there are spare the request
+                // of an empty page after the last one.
+                self.done = page.meta.is_some_and(|m| m.is_last_page());
+                self.next += 1;
+                Some(Ok(page))
+            }
+            // Asking for a page past the last one may be answered with a 404.
+            Err(Error::Status(404)) if self.next > 1 => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Iterator over the torrents found by a search. Pages of results are fetched
+/// only when they are needed so `client.search_results(&query).take(20)` does
+/// not download more than the first page.
+///
+/// It is created by [`NyaaClient::search_results`]. It stops after the last
+/// torrent, after the query's [`limit`] or after the first error.
+///
+/// [`NyaaClient::search_results`]: ./struct.NyaaClient.html#method.search_results
+/// [`limit`]: ./struct.SearchQuery.html#method.limit
+#[derive(Debug, Clone)]
+pub struct SearchResults<'a> {
+    pages: SearchPages<'a>,
+    torrents: std::vec::IntoIter<Torrent>,
+    remaining: Option<usize>,
+}
+
+impl<'a> SearchResults<'a> {
+    pub(crate) fn new(client: &'a NyaaClient, query: &SearchQuery) -> SearchResults<'a> {
+        SearchResults {
+            pages: SearchPages::new(client, query),
+            torrents: vec![].into_iter(),
+            remaining: query.limit,
+        }
+    }
+}
+
+impl Iterator for SearchResults<'_> {
+    type Item = Result<Torrent, Error>;
+
+    fn next(&mut self) -> Option<Result<Torrent, Error>> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+
+        loop {
+            if let Some(torrent) = self.torrents.next() {
+                self.remaining = self.remaining.map(|r| r - 1);
+                return Some(Ok(torrent));
+            }
+
+            match self.pages.next()? {
+                Ok(page) => self.torrents = page.torrents.into_iter(),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
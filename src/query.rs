@@ -0,0 +1,186 @@
+use crate::category::*;
+
+/// Struct that describes a search on Nyaa.si: what to look for, in which
+/// category, with which filter and how the results should be sorted.
+///
+/// `SearchQuery` is built by chaining its methods:
+///
+/// ```
+/// use nyaadesu::{Anime, Category, Filter, Order, SearchQuery, Sort};
+///
+/// let query = SearchQuery::new("little witch academia")
+///     .category(Category::Anime(Anime::EnglishTranslated))
+///     .filter(Filter::TrustedOnly)
+///     .sort(Sort::Seeders)
+///     .order(Order::Descending);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchQuery {
+    terms: String,
+    category: CategoryFilter,
+    filter: Filter,
+    sort: Sort,
+    order: Order,
+    pub(crate) limit: Option<usize>,
+    pub(crate) max_pages: Option<u32>,
+}
+
+impl SearchQuery {
+    /// Create a query that searches for `terms` in every category, without
+    /// any filter and with the newest torrents first (just like Nyaa.si does
+    /// by default).
+    pub fn new(terms: &str) -> SearchQuery {
+        SearchQuery {
+            terms: terms.to_string(),
+            category: CategoryFilter::All,
+            filter: Filter::NoFilter,
+            sort: Sort::Date,
+            order: Order::Descending,
+            limit: None,
+            max_pages: None,
+        }
+    }
+
+    /// Restrict the search to a category. Both a [`Category`] and a
+    /// [`CategoryFilter`] (e.g. "all of Anime") are accepted.
+    ///
+    /// [`Category`]: ./enum.Category.html
+    /// [`CategoryFilter`]: ./enum.CategoryFilter.html
+    pub fn category<C: Into<CategoryFilter>>(mut self, category: C) -> SearchQuery {
+        self.category = category.into();
+        self
+    }
+
+    /// Set the filter applied to the search.
+    pub fn filter(mut self, filter: Filter) -> SearchQuery {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the key results are sorted by.
+    pub fn sort(mut self, sort: Sort) -> SearchQuery {
+        self.sort = sort;
+        self
+    }
+
+    /// Set the order of the results.
+    pub fn order(mut self, order: Order) -> SearchQuery {
+        self.order = order;
+        self
+    }
+
+    /// Stop a search after `limit` torrents have been found.
+    pub fn limit(mut self, limit: usize) -> SearchQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Stop a search after `max_pages` pages of results have been fetched.
+    pub fn max_pages(mut self, max_pages: u32) -> SearchQuery {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Terms that are searched for.
+    pub fn terms(&self) -> &str {
+        &self.terms
+    }
+
+    // Query parameters that select the p-th page of results on Nyaa.si.
+    pub(crate) fn params(&self, p: u32) -> Vec<(&'static str, String)> {
+        vec![
+            ("f", self.filter.code().to_string()),
+            ("c", self.category.code().to_string()),
+            ("q", self.terms.clone()),
+            ("s", self.sort.code().to_string()),
+            ("o", self.order.code().to_string()),
+            ("p", p.to_string()),
+        ]
+    }
+
+    // Query parameters that select the RSS feed of the query. The feed has no
+    // pages and it is always sorted by date.
+    pub(crate) fn rss_params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("page", "rss".to_string()),
+            ("f", self.filter.code().to_string()),
+            ("c", self.category.code().to_string()),
+            ("q", self.terms.clone()),
+        ]
+    }
+}
+
+/// Enum that encodes the filters Nyaa.si can apply to a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Filter {
+    /// Show every torrent.
+    NoFilter,
+    /// Hide torrents marked as remakes.
+    NoRemakes,
+    /// Show only torrents uploaded by trusted users.
+    TrustedOnly,
+}
+
+impl Filter {
+    fn code(self) -> &'static str {
+        match self {
+            Filter::NoFilter => "0",
+            Filter::NoRemakes => "1",
+            Filter::TrustedOnly => "2",
+        }
+    }
+}
+
+/// Enum that encodes the keys search results can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Sort {
+    /// Date of publication.
+    Date,
+    /// Total size of the torrent's files.
+    Size,
+    /// Number of seeders.
+    Seeders,
+    /// Number of leechers.
+    Leechers,
+    /// Number of completed downloads.
+    Downloads,
+    /// Number of comments.
+    Comments,
+}
+
+impl Sort {
+    fn code(self) -> &'static str {
+        match self {
+            // Nyaa.si sorts by date using the torrent's id.
+            Sort::Date => "id",
+            Sort::Size => "size",
+            Sort::Seeders => "seeders",
+            Sort::Leechers => "leechers",
+            Sort::Downloads => "downloads",
+            Sort::Comments => "comments",
+        }
+    }
+}
+
+/// Enum that encodes the order of search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+impl Order {
+    fn code(self) -> &'static str {
+        match self {
+            Order::Ascending => "asc",
+            Order::Descending => "desc",
+        }
+    }
+}
@@ -0,0 +1,214 @@
+extern crate reqwest;
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::rss::*;
+use crate::types::*;
+
+/// Struct that represents when and how long a client waits to try a request
+/// again after it failed.
+///
+/// Requests are tried again when Nyaa.si answers with 429 (too many requests)
+/// or with one of the statuses it uses when it is down (500, 502, 503 or 504),
+/// when they time out and when the connection fails. Other errors (e.g. a 404)
+/// are returned right away.
+///
+/// The n-th retry waits `initial_delay * 2^(n - 1)`, capped at `max_delay`,
+/// and with jitter a random duration between half of that and all of it so
+/// that clients throttled together don't come back together. When the server
+/// says how long to wait with a `Retry-After` header that is waited instead,
+/// unless it is longer than `max_delay`: then the request fails right away.
+///
+/// Clients don't retry unless configured with
+/// [`NyaaClientBuilder::retry`]:
+///
+/// ```
+/// use std::time::Duration;
+/// use nyaadesu::RetryPolicy;
+///
+/// let policy = RetryPolicy::new()
+///     .max_retries(5)
+///     .initial_delay(Duration::from_millis(500))
+///     .max_delay(Duration::from_secs(30));
+/// ```
+///
+/// [`NyaaClientBuilder::retry`]: ./struct.NyaaClientBuilder.html#method.retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Create a policy that tries requests again up to 3 times, waiting 1
+    /// second before the first retry and at most 60 seconds, with jitter.
+    pub fn new() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            jitter: true,
+        }
+    }
+
+    /// Create a policy that never tries requests again.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::new().max_retries(0)
+    }
+
+    /// Set how many times a request is tried again before its error is
+    /// returned.
+    pub fn max_retries(mut self, max_retries: u32) -> RetryPolicy {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set how long to wait before the first retry.
+    pub fn initial_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the longest wait between two tries, including the ones asked for
+    /// with `Retry-After`.
+    pub fn max_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Choose whether to wait a random part of each delay (on by default).
+    pub fn jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    // How long to wait before trying again a request that failed with error
+    // after some retries, or None to give up.
+    pub(crate) fn delay(
+        &self,
+        retries: u32,
+        error: &Error,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if retries >= self.max_retries || !is_transient(error) {
+            return None;
+        }
+
+        if let Some(retry_after) = retry_after {
+            return if retry_after <= self.max_delay {
+                Some(retry_after)
+            } else {
+                None
+            };
+        }
+
+        let delay = self
+            .initial_delay
+            .checked_mul(2u32.saturating_pow(retries))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        if self.jitter {
+            Some(delay / 2 + delay.mul_f64(random_fraction() / 2.0))
+        } else {
+            Some(delay)
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::new()
+    }
+}
+
+// Whether a request that failed with error may succeed if tried again.
+fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Status(status) => [429, 500, 502, 503, 504].contains(status),
+        Error::Timeout(_) => true,
+        Error::Request(e) => e.is_connect(),
+        _ => false,
+    }
+}
+
+// Random number in [0, 1), good enough to spread retries.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// How long the server asked to wait with the Retry-After header of its
+// answer, either as a number of seconds or as an HTTP date (e.g. "Wed, 21 Oct
+// 2015 07:28:00 GMT").
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = parse_date(value)?.to_system_time()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::from_secs(0)),
+    )
+}
+
+// Token bucket shared by the clones of a client: it holds up to `capacity`
+// requests and gets `rate` more each second. Requests made when it is empty
+// wait for their turn.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    capacity: f64,
+    rate: f64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests: u32, per: Duration) -> RateLimiter {
+        let capacity = f64::from(requests.max(1));
+        RateLimiter {
+            capacity,
+            rate: capacity / per.as_secs_f64().max(f64::MIN_POSITIVE),
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: capacity,
+                last: Instant::now(),
+            })),
+        }
+    }
+
+    // Take a token and return how long to wait before using it. Tokens are
+    // taken even when the bucket is empty so requests go in the order they
+    // asked.
+    pub(crate) fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.last).as_secs_f64() * self.rate;
+        bucket.tokens = (bucket.tokens + refill).min(self.capacity) - 1.0;
+        bucket.last = now;
+
+        // A limit spread over a very long time (e.g. `Duration::MAX`) can ask
+        // to wait longer than a Duration holds.
+        if bucket.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::try_from_secs_f64(-bucket.tokens / self.rate).unwrap_or(Duration::MAX)
+        }
+    }
+}
@@ -0,0 +1,217 @@
+extern crate xml;
+
+use std::collections::HashMap;
+
+use crate::category::*;
+use crate::magnet::*;
+use crate::site::*;
+use crate::size::*;
+use crate::timestamp::*;
+use crate::types::*;
+
+/// Parse Nyaa.si's RSS feed (e.g. the xml of `https://nyaa.si/?page=rss`)
+/// that was already downloaded.
+///
+/// The feed is more stable than the html pages but it only holds the first
+/// page of results. It has no magnet links so they are built from the info
+/// hashes with Nyaa.si's trackers: use [`parse_rss_from`] for Sukebei's feed.
+///
+/// [`parse_rss_from`]: ./fn.parse_rss_from.html
+pub fn parse_rss(xml: &str) -> Result<Vec<Torrent>, Error> {
+    read_rss(&mut xml.as_bytes())
+}
+
+/// Like [`parse_rss`] but read the xml from a reader.
+///
+/// [`parse_rss`]: ./fn.parse_rss.html
+pub fn read_rss<R: std::io::Read>(r: &mut R) -> Result<Vec<Torrent>, Error> {
+    read_rss_from(r, Site::Nyaa)
+}
+
+/// Like [`parse_rss`] but for the feed of the given site, whose trackers go in
+/// the magnet links.
+///
+/// [`parse_rss`]: ./fn.parse_rss.html
+pub fn parse_rss_from(xml: &str, site: Site) -> Result<Vec<Torrent>, Error> {
+    read_rss_from(&mut xml.as_bytes(), site)
+}
+
+/// Like [`parse_rss_from`] but read the xml from a reader.
+///
+/// [`parse_rss_from`]: ./fn.parse_rss_from.html
+pub fn read_rss_from<R: std::io::Read>(r: &mut R, site: Site) -> Result<Vec<Torrent>, Error> {
+    let mut torrents = vec![];
+
+    // Fields of the <item> that is being read, by name.
+    let mut item: Option<HashMap<String, String>> = None;
+    let mut field = None;
+
+    for event in xml::reader::EventReader::new(r) {
+        let event = event.map_err(|_| ScrapingError::new(PageKind::Rss, None, "feed"))?;
+        match event {
+            xml::reader::XmlEvent::StartElement { name, .. } => {
+                if name.local_name == "item" {
+                    item = Some(HashMap::new());
+                } else {
+                    field = Some(name.local_name);
+                }
+            }
+            xml::reader::XmlEvent::Characters(text) | xml::reader::XmlEvent::CData(text) => {
+                if let (Some(fields), Some(field)) = (&mut item, &field) {
+                    fields.entry(field.clone()).or_default().push_str(&text);
+                }
+            }
+            xml::reader::XmlEvent::EndElement { name } => {
+                if name.local_name == "item" {
+                    if let Some(fields) = item.take() {
+                        torrents.push(get_torrent(&fields, torrents.len(), site)?);
+                    }
+                }
+                field = None;
+            }
+            _ => (),
+        }
+    }
+
+    Ok(torrents)
+}
+
+// Build a torrent from the fields of the i-th <item>.
+fn get_torrent(
+    fields: &HashMap<String, String>,
+    i: usize,
+    site: Site,
+) -> Result<Torrent, ScrapingError> {
+    let missing = |field| ScrapingError::new(PageKind::Rss, Some(i), field);
+    let field = |name| {
+        fields
+            .get(name)
+            .map(|f| f.trim())
+            .ok_or_else(|| missing(name))
+    };
+
+    let unknown = |field, value: &str| missing(field).with_value(Some(value.to_string()));
+    let number = |name| {
+        let value = field(name)?;
+        value.parse::<u32>().map_err(|_| unknown(name, value))
+    };
+
+    let name = field("title")?.to_string();
+    let category = match fields.get("category").and_then(|c| c.trim().parse().ok()) {
+        Some(category) => category,
+        None => {
+            let code = field("categoryId")?;
+            Category::from_site_code(site, code).ok_or_else(|| unknown("categoryId", code))?
+        }
+    };
+    let view_url = field("guid")?.to_string();
+    let id = view_id(&view_url).ok_or_else(|| unknown("guid", &view_url))?;
+    let torrent_file = field("link")?.to_string();
+    let info_hash = field("infoHash")?;
+    let info_hash = info_hash
+        .parse()
+        .map_err(|_| unknown("infoHash", info_hash))?;
+    let magnet_link = magnet_link(info_hash, &name, site);
+    let size = field("size")?;
+    let size = ByteSize::parse(size).ok_or_else(|| unknown("size", size))?;
+    let date = field("pubDate")?;
+    let date = parse_date(date).ok_or_else(|| unknown("pubDate", date))?;
+    let seeders = number("seeders")?;
+    let leechers = number("leechers")?;
+    let completed_downloads = number("downloads")?;
+    let comments = number("comments")?;
+    let status = Status::from_flags(field("trusted")? == "Yes", field("remake")? == "Yes");
+
+    Ok(Torrent {
+        id,
+        category,
+        name,
+        links: TorrentLinks {
+            download_url: Some(torrent_file),
+            magnet: Some(magnet_link),
+            view_url: Some(view_url),
+        },
+        size,
+        date,
+        seeders,
+        leechers,
+        completed_downloads,
+        comments,
+        status,
+    })
+}
+
+// Build the magnet link of a torrent the same way the site does.
+fn magnet_link(info_hash: InfoHash, name: &str, site: Site) -> String {
+    let mut magnet = Magnet::new(info_hash);
+    magnet.name = Some(name.to_string());
+    magnet.trackers = site.trackers().iter().map(|t| t.to_string()).collect();
+
+    magnet.to_uri()
+}
+
+// Convert a RFC 2822 date (e.g. "Sun, 13 Sep 2020 12:26:40 -0000") to an unix
+// timestamp.
+pub(crate) fn parse_date(date: &str) -> Option<Timestamp> {
+    let parts = date.split_whitespace().collect::<Vec<_>>();
+
+    // The day of the week is optional.
+    let parts = if parts.first()?.ends_with(',') {
+        &parts[1..]
+    } else {
+        &parts[..]
+    };
+
+    let day = parts.first()?.parse::<i64>().ok()?;
+    let month = match *parts.get(1)? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year = parts.get(2)?.parse::<i64>().ok()?;
+
+    let time = parts
+        .get(3)?
+        .split(':')
+        .map(|t| t.parse::<i64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    // Offset from UTC as "+hhmm" or "-hhmm" (or "GMT"/"UTC").
+    let zone = parts.get(4).unwrap_or(&"+0000");
+    let hhmm = zone.get(1..)?.parse::<i64>().unwrap_or(0);
+
+    // Reject out of range values (this also keeps the arithmetic below from
+    // overflowing).
+    if !(1970..=9999).contains(&year)
+        || !(1..=31).contains(&day)
+        || time.len() > 3
+        || time.iter().any(|t| !(0..=60).contains(t))
+        || !(0..=2400).contains(&hhmm)
+    {
+        return None;
+    }
+
+    let seconds = time.first()? * 3600 + time.get(1)? * 60 + time.get(2).unwrap_or(&0);
+    let offset = if zone.starts_with('-') {
+        -((hhmm / 100) * 3600 + (hhmm % 100) * 60)
+    } else {
+        (hhmm / 100) * 3600 + (hhmm % 100) * 60
+    };
+
+    let timestamp = days_from_civil(year, month, day) * 86400 + seconds - offset;
+    if timestamp < 0 {
+        None
+    } else {
+        Some(Timestamp::from_unix(timestamp as u64))
+    }
+}
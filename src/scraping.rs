@@ -1,70 +1,145 @@
 extern crate html5ever;
-extern crate reqwest;
 
 use html5ever::tendril::TendrilSink;
-use std::rc::Rc;
 
+use crate::category::*;
+use crate::check::*;
+use crate::magnet::*;
+use crate::size::*;
+use crate::timestamp::*;
 use crate::types::*;
 
-// Enum that encodes diffent types of pages: page with torrents, page with no
-// results (searching for somehting that is not on Nyaa.si) and page with no
-// more results (e.g. page 6 of a query that return 4 pages of results).
-enum Page {
+/// Enum that encodes diffent types of pages: page with torrents, page with no
+/// results (searching for somehting that is not on Nyaa.si) and page with no
+/// more results (e.g. page 6 of a query that return 4 pages of results).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Page {
     Torrents,
     NoTorrents,
     NoMoreTorrents,
 }
 
-/// Search Nyaa.si for torrents by name.
+/// Enum that encodes how to deal with rows of search results that can't be
+/// scraped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ParseMode {
+    /// Fail the whole page if a single row can't be scraped.
+    #[default]
+    Strict,
+    /// Skip rows that can't be scraped and report them in
+    /// [`SearchPage::errors`].
+    ///
+    /// [`SearchPage::errors`]: ./struct.SearchPage.html#structfield.errors
+    Lenient,
+}
+
+/// Struct that represents a page of search results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SearchPage {
+    /// What kind of page this is.
+    pub state: Page,
+    /// Torrents listed in the page (empty if the page has none).
+    pub torrents: Vec<Torrent>,
+    /// Rows that could not be scraped. This is always empty unless the page
+    /// was parsed in [`ParseMode::Lenient`].
+    ///
+    /// [`ParseMode::Lenient`]: ./enum.ParseMode.html#variant.Lenient
+    pub errors: Vec<RowError>,
+    /// Number of results and pages of the search, `None` if the page does
+    /// not show them.
+    pub meta: Option<SearchMeta>,
+}
+
+/// Struct that represents what a page of search results tells about the
+/// whole search ("Displaying results 151-225 out of 1000 results" and the
+/// pagination widget).
 ///
-/// While using this function be sure to check for the Error (read [module
-/// level documentation] for more).
+/// Nyaa.si never shows more than 1000 results for a search so neither does
+/// `total_results`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchMeta {
+    /// Number of results of the search.
+    pub total_results: u32,
+    /// Number of results in a full page. On a search with a single page this
+    /// is the number of results.
+    pub page_size: u32,
+    /// Number of this page, starting from 1.
+    pub current_page: u32,
+    /// Number of the last page of results.
+    pub last_page: u32,
+}
+
+impl SearchMeta {
+    /// Whether there are no more pages after this one.
+    pub fn is_last_page(&self) -> bool {
+        self.current_page >= self.last_page
+    }
+}
+
+/// Struct that represents a row of search results that could not be scraped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RowError {
+    /// What went wrong: the index of the row and the field that could not be
+    /// extracted.
+    pub error: ScrapingError,
+    /// Html of the row.
+    pub html: String,
+}
+
+/// Parse a page of search results (e.g. the html of
+/// `https://nyaa.si/?q=madoka`) that was already downloaded.
 ///
-/// [module level documentation]: ./index.html
-pub fn torrent_search(s: &str) -> Result<Vec<Torrent>, Error> {
-    let client = reqwest::Client::new();
+/// This does not do any network request so you can use it with your own HTTP
+/// client or with saved pages.
+pub fn parse_search_page(html: &str) -> Result<SearchPage, Error> {
+    parse_search_page_with(html, ParseMode::Strict)
+}
 
-    let mut all_the_torrents = vec![];
+/// Like [`parse_search_page`] but read the html from a reader.
+///
+/// [`parse_search_page`]: ./fn.parse_search_page.html
+pub fn read_search_page<R: std::io::Read>(r: &mut R) -> Result<SearchPage, Error> {
+    read_search_page_with(r, ParseMode::Strict)
+}
 
-    for n in 0.. {
-        let mut torrents = torrent_search_page(&client, s, n)?;
-        if torrents.is_empty() {
-            break;
-        } else {
-            all_the_torrents.append(&mut torrents);
-        }
-    }
+/// Like [`parse_search_page`] but choose what to do with rows that can't be
+/// scraped.
+///
+/// [`parse_search_page`]: ./fn.parse_search_page.html
+pub fn parse_search_page_with(html: &str, mode: ParseMode) -> Result<SearchPage, Error> {
+    read_search_page_with(&mut html.as_bytes(), mode)
+}
 
-    Ok(all_the_torrents)
-}
-
-// Get torrents from the n-th result page of the query by name.
-fn torrent_search_page(c: &reqwest::Client, s: &str, n: i32) -> Result<Vec<Torrent>, Error> {
-    let page = fetch_page(&c, s, n);
-    match page {
-        Ok(mut req) => {
-            let dom = html5ever::driver::parse_document(
-                html5ever::rcdom::RcDom::default(),
-                html5ever::driver::ParseOpts::default(),
-            )
-            .from_utf8()
-            .read_from(&mut req)
-            .unwrap();
-
-            match identify_page(&dom.document) {
-                Page::Torrents => {
-                    let torrents = parse_page(&dom.document);
-                    match torrents {
-                        Some(ts) => Ok(ts),
-                        None => Err(Error::Scraping),
-                    }
-                }
-                Page::NoMoreTorrents => Ok(vec![]),
-                Page::NoTorrents => Ok(vec![]),
-            }
-        }
-        Err(_) => Err(Error::Request),
-    }
+/// Like [`read_search_page`] but choose what to do with rows that can't be
+/// scraped.
+///
+/// [`read_search_page`]: ./fn.read_search_page.html
+pub fn read_search_page_with<R: std::io::Read>(
+    r: &mut R,
+    mode: ParseMode,
+) -> Result<SearchPage, Error> {
+    let dom = read_document(r)?;
+
+    let state = identify_page(&dom.document);
+    let (torrents, errors) = match state {
+        Page::Torrents => parse_page(&dom.document, mode)?,
+        Page::NoMoreTorrents => (vec![], vec![]),
+        Page::NoTorrents => (vec![], vec![]),
+    };
+
+    Ok(SearchPage {
+        state,
+        torrents,
+        errors,
+        meta: get_meta(&dom.document),
+    })
 }
 
 // Check if the page contains any torrent or not.
@@ -72,340 +147,585 @@ fn identify_page(handle: &html5ever::rcdom::Handle) -> Page {
     // Get the eventual table body.
     match get_table_body(handle) {
         Some(t) => {
-            if t.children.borrow().iter().count() > 1 {
-                Page::Torrents
-            } else {
+            if get_rows(&t).is_empty() {
                 Page::NoMoreTorrents
+            } else {
+                Page::Torrents
             }
         }
         None => Page::NoTorrents,
     }
 }
 
-// Using the pHTTP client c return the p-th page of results obtained by searching for s.
-fn fetch_page(c: &reqwest::Client, s: &str, p: i32) -> reqwest::Result<reqwest::Response> {
-    let params = [("f", "0"), ("c", "0_0"), ("q", s), ("p", &p.to_string())];
-    c.get("https://nyaa.si").query(&params).send()
+// Extract the number of results and pages from the text above the pagination
+// widget ("Displaying results 151-225 out of 1000 results.") and from the
+// widget itself.
+fn get_meta(handle: &html5ever::rcdom::Handle) -> Option<SearchMeta> {
+    let info = find(handle, &|n| has_class(n, "pagination-page-info"))?;
+    let info = text_content(&info);
+    let words = info.split_whitespace().collect::<Vec<_>>();
+
+    let range = words.iter().find(|w| w.contains('-'))?;
+    let mut range = range.splitn(2, '-').map(|n| n.parse::<u32>());
+    let (start, end) = (range.next()?.ok()?, range.next()?.ok()?);
+    let of = words.iter().position(|w| *w == "of")?;
+    let total_results = words.get(of + 1)?.parse::<u32>().ok()?;
+
+    // Numbers of the pages linked by the widget, and the one of this page.
+    let mut pages = vec![];
+    let mut active = None;
+    if let Some(widget) = find(handle, &|n| is_tag(n, "ul") && has_class(n, "pagination")) {
+        for item in find_all(&widget, &|n| is_tag(n, "li")) {
+            let number = text_content(&item)
+                .split_whitespace()
+                .next()
+                .and_then(|n| n.parse::<u32>().ok());
+            if let Some(number) = number {
+                pages.push(number);
+                if has_class(&item, "active") {
+                    active = Some(number);
+                }
+            }
+        }
+    }
+
+    let current_page = match active {
+        Some(page) => page,
+        None if start <= 1 => 1,
+        None => return None,
+    };
+    let page_size = if total_results == 0 {
+        0
+    } else if current_page > 1 {
+        start.checked_sub(1)? / (current_page - 1)
+    } else {
+        end.checked_sub(start)?.checked_add(1)?
+    };
+    let counted = match page_size {
+        0 => current_page,
+        size => total_results / size + u32::from(total_results % size != 0),
+    };
+    let last_page = pages
+        .into_iter()
+        .chain(vec![counted, current_page])
+        .max()
+        .unwrap_or(current_page);
+
+    Some(SearchMeta {
+        total_results,
+        page_size,
+        current_page,
+        last_page,
+    })
 }
 
-// Parse an entire html page for torrents.
-fn parse_page(handle: &html5ever::rcdom::Handle) -> Option<Vec<Torrent>> {
-    let mut torrents = vec![];
+// Check which fields of a page of search results can be scraped.
+pub(crate) fn check_search_document(handle: &html5ever::rcdom::Handle) -> Vec<FieldCheck> {
+    let mut tally = Tally::new(PageKind::Search);
+
+    let rows = get_table_body(handle)
+        .map(|t| get_rows(&t))
+        .unwrap_or_default();
+    tally.record(
+        "table",
+        if rows.is_empty() {
+            Found::Missing
+        } else {
+            Found::Parsed
+        },
+    );
+
+    for row in rows.iter() {
+        let cells = Cells::new(row);
+
+        let category = cells
+            .category
+            .as_ref()
+            .and_then(|cell| find(cell, &|n| is_tag(n, "a")));
+        tally.record(
+            "category",
+            match category.as_ref().and_then(|a| get_attribute(a, "title")) {
+                Some(title) if title.parse::<Category>().is_ok() => Found::Parsed,
+                Some(title) => Found::Unknown(title),
+                None if get_category(&cells).is_some() => Found::Parsed,
+                None => Found::Missing,
+            },
+        );
+
+        tally.record("name", found(get_name(&cells)));
+
+        let view_url = cells
+            .name
+            .as_ref()
+            .and_then(get_title)
+            .and_then(|title| get_attribute(&title, "href"));
+        tally.record(
+            "id",
+            match view_url {
+                Some(url) if view_id(&url).is_some() => Found::Parsed,
+                Some(url) => Found::Unknown(url),
+                None => Found::Missing,
+            },
+        );
+
+        let links = get_links(&cells).unwrap_or_default();
+        tally.record("download link", found(links.download_url));
+        tally.record(
+            "magnet",
+            match links.magnet {
+                Some(magnet) if magnet.parse::<Magnet>().is_ok() => Found::Parsed,
+                Some(magnet) => Found::Unknown(magnet),
+                None => Found::Missing,
+            },
+        );
+
+        tally.record("size", parsed_text(cells.size.as_ref(), ByteSize::parse));
+        tally.record(
+            "date",
+            match cells
+                .date
+                .as_ref()
+                .and_then(|d| get_attribute(d, "data-timestamp"))
+            {
+                Some(date) if date.trim().parse::<u64>().is_ok() => Found::Parsed,
+                Some(date) => Found::Unknown(date),
+                None => Found::Missing,
+            },
+        );
+        for (i, field) in ["seeders", "leechers", "completed downloads"]
+            .iter()
+            .enumerate()
+        {
+            tally.record(
+                field,
+                parsed_text(cells.numbers.get(i), |n| n.parse::<u32>().ok()),
+            );
+        }
 
-    let table_body = get_table_body(handle)?;
-    for row in table_body
-        .children
-        .borrow()
-        .iter()
-        .filter(|r| is_element(r))
-    {
-        let category = get_category(row)?;
-        let name = get_name(row)?;
-        let links = get_links(row)?;
-        let size = get_size(row)?;
-        let date = get_date(row)?;
-        let seeders = get_seeders(row)?;
-        let leechers = get_leechers(row)?;
-        let completed_downloads = get_completed_downloads(row)?;
-
-        torrents.push(Torrent {
-            category,
-            name,
-            links,
-            size,
-            date,
-            seeders,
-            leechers,
-            completed_downloads,
-        });
+        let badge = cells
+            .name
+            .as_ref()
+            .and_then(|cell| find(cell, &|n| is_tag(n, "a") && has_class(n, "comments")));
+        tally.record(
+            "comments",
+            match badge {
+                Some(badge) => parsed_text(Some(&badge), |n| n.parse::<u32>().ok()),
+                // Torrents without comments have no badge.
+                None => Found::Parsed,
+            },
+        );
     }
 
-    Some(torrents)
-}
-
-// Dive deep in the webpage and extract the table body (i.e. <tbody>) of the main table.
-fn get_table_body(handle: &html5ever::rcdom::Handle) -> Option<Rc<html5ever::rcdom::Node>> {
-    // Get to the <div class="container"> that holds the table.
-    let document_children = &handle.children.borrow();
-    let html = document_children.get(1)?;
-    let html_children = &html.children.borrow();
-    let body = html_children.get(2)?;
-    let body_children = &body.children.borrow();
-    let container = body_children.get(5)?;
-    let container_children = &container.children.borrow();
-
-    // Get the <div class="table-responsive"> that holds the table.
-    let table_div = get_table_div(container_children.to_vec())?;
-
-    // Get the <tbody>.
-    let table_div_children = &table_div.children.borrow();
-    let table = table_div_children.get(1)?;
-    let table_children = &table.children.borrow();
-    let table_body = table_children.get(3)?;
-
-    Some(table_body.clone())
-}
-
-// Get the <div class="table-responsive"> that holds the table from the
-// container's childs.
-fn get_table_div(v: Vec<Rc<html5ever::rcdom::Node>>) -> Option<Rc<html5ever::rcdom::Node>> {
-    let mut result = None;
-    // let mut result = html5ever::rcdom::Node::new(html5ever::rcdom::NodeData::Document);
-    for node in v.iter() {
-        if let html5ever::rcdom::NodeData::Element { attrs, .. } = &node.data {
-            if attrs.borrow().iter().any(|att| {
-                &att.name.local == "class" && &att.value.to_string() == "table-responsive"
-            }) {
-                result = Some(node.clone());
-                break;
+    tally.record("meta", found(get_meta(handle)));
+
+    tally.into_fields()
+}
+
+// What was found looking for a field that is either there or not.
+pub(crate) fn found<T>(value: Option<T>) -> Found {
+    match value {
+        Some(_) => Found::Parsed,
+        None => Found::Missing,
+    }
+}
+
+// What was found parsing the trimmed text of a node with f.
+pub(crate) fn parsed_text<T, F>(node: Option<&html5ever::rcdom::Handle>, f: F) -> Found
+where
+    F: Fn(&str) -> Option<T>,
+{
+    match node {
+        Some(node) => {
+            let text = text_content(node).trim().to_string();
+            match f(&text) {
+                Some(_) => Found::Parsed,
+                None => Found::Unknown(text),
             }
         }
+        None => Found::Missing,
     }
+}
 
-    result
+// Parse an html document.
+pub(crate) fn read_document<R: std::io::Read>(
+    r: &mut R,
+) -> std::io::Result<html5ever::rcdom::RcDom> {
+    html5ever::driver::parse_document(
+        html5ever::rcdom::RcDom::default(),
+        html5ever::driver::ParseOpts::default(),
+    )
+    .from_utf8()
+    .read_from(r)
 }
 
-// Identifies an Element node.
-fn is_element(n: &html5ever::rcdom::Node) -> bool {
-    match n.data {
-        html5ever::rcdom::NodeData::Element { .. } => true,
-        _ => false,
+// Parse an entire html page for torrents. In lenient mode rows that can't be
+// scraped are returned apart instead of failing the whole page.
+fn parse_page(
+    handle: &html5ever::rcdom::Handle,
+    mode: ParseMode,
+) -> Result<(Vec<Torrent>, Vec<RowError>), ScrapingError> {
+    let mut torrents = vec![];
+    let mut errors = vec![];
+
+    let table_body = get_table_body(handle)
+        .ok_or_else(|| ScrapingError::new(PageKind::Search, None, "table"))?;
+    for (i, row) in get_rows(&table_body).iter().enumerate() {
+        match parse_row(row, i) {
+            Ok(torrent) => torrents.push(torrent),
+            Err(error) => match mode {
+                ParseMode::Strict => return Err(error),
+                ParseMode::Lenient => errors.push(RowError {
+                    error,
+                    html: serialize_node(row),
+                }),
+            },
+        }
     }
+
+    Ok((torrents, errors))
 }
 
-// Extract torrent name from a table row.
-fn get_name(row: &html5ever::rcdom::Node) -> Option<String> {
-    let mut result = None;
+// Parse the i-th row of the table of results.
+fn parse_row(row: &html5ever::rcdom::Handle, i: usize) -> Result<Torrent, ScrapingError> {
+    let field = |field| ScrapingError::new(PageKind::Search, Some(i), field);
+    let cells = Cells::new(row);
+
+    let category = get_category(&cells)
+        .ok_or_else(|| field("category").with_value(get_category_text(&cells)))?;
+    let name = get_name(&cells).ok_or_else(|| field("name"))?;
+    let links = get_links(&cells).ok_or_else(|| field("links"))?;
+    let id = links
+        .view_url
+        .as_deref()
+        .and_then(view_id)
+        .ok_or_else(|| field("id"))?;
+    let size =
+        get_size(&cells).ok_or_else(|| field("size").with_value(get_text(cells.size.as_ref())))?;
+    let date = get_date(&cells).ok_or_else(|| {
+        field("date").with_value(
+            cells
+                .date
+                .as_ref()
+                .and_then(|d| get_attribute(d, "data-timestamp")),
+        )
+    })?;
+    let seeders = get_number(&cells, 0)
+        .ok_or_else(|| field("seeders").with_value(get_text(cells.numbers.first())))?;
+    let leechers = get_number(&cells, 1)
+        .ok_or_else(|| field("leechers").with_value(get_text(cells.numbers.get(1))))?;
+    let completed_downloads = get_number(&cells, 2)
+        .ok_or_else(|| field("completed downloads").with_value(get_text(cells.numbers.get(2))))?;
+    let comments = get_comments(&cells).ok_or_else(|| field("comments"))?;
+    let status = Status::from_flags(has_class(row, "success"), has_class(row, "danger"));
+
+    Ok(Torrent {
+        id,
+        category,
+        name,
+        links,
+        size,
+        date,
+        seeders,
+        leechers,
+        completed_downloads,
+        comments,
+        status,
+    })
+}
 
-    // Get to the cell that contains the title.
-    let row_children = &row.children.borrow();
-    let name_cell = row_children.get(3)?;
-    let name_cell_children = &name_cell.children.borrow();
+// Turn a node back into html.
+fn serialize_node(node: &html5ever::rcdom::Handle) -> String {
+    let mut html = vec![];
+    let opts = html5ever::serialize::SerializeOpts {
+        traversal_scope: html5ever::serialize::TraversalScope::IncludeNode,
+        create_missing_parent: true,
+        ..Default::default()
+    };
 
-    // Get the title nodes (we should always have only one).
-    let title_nodes = name_cell_children
-        .iter()
-        .filter(|link| is_title(&link))
-        .collect::<Vec<_>>();
-    let title_node = &title_nodes.get(0)?;
-
-    // Extract the name from the title node.
-    let title_node_children = &title_node.children.borrow();
-    let title = title_node_children.get(0)?;
-    if let html5ever::rcdom::NodeData::Text { contents } = &title.data {
-        result = Some(contents.borrow().to_string());
-    }
+    // Writing to a Vec can't fail.
+    let _ = html5ever::serialize(&mut html, node, opts);
 
-    result
+    String::from_utf8_lossy(&html).into_owned()
 }
 
-// Identifies a link node (i.e. <a>) that contains the title of a torrent.
-fn is_title(node: &html5ever::rcdom::Node) -> bool {
-    match &node.data {
-        html5ever::rcdom::NodeData::Element { attrs, .. } => {
-            attrs.borrow().iter().all(|att| !is_class(att))
-        }
-        _ => false,
-    }
+// Find the <tbody> of the table of results (<table class="torrent-list">).
+fn get_table_body(handle: &html5ever::rcdom::Handle) -> Option<html5ever::rcdom::Handle> {
+    let table = find(handle, &|n| {
+        is_tag(n, "table") && has_class(n, "torrent-list")
+    })?;
+
+    find(&table, &|n| is_tag(n, "tbody"))
 }
 
-// Identifies the attribute "class".
-fn is_class(att: &html5ever::tree_builder::Attribute) -> bool {
-    &att.name.local == "class"
+// Get the rows (<tr>) of the table of results.
+fn get_rows(table_body: &html5ever::rcdom::Handle) -> Vec<html5ever::rcdom::Handle> {
+    element_children(table_body)
+        .into_iter()
+        .filter(|n| is_tag(n, "tr"))
+        .collect()
 }
 
-// Extract torrent category from a table row.
-fn get_category(row: &html5ever::rcdom::Node) -> Option<Category> {
-    // Get to the node that contains the category.
-    let row_children = &row.children.borrow();
-    let category_cell = row_children.get(1)?;
-    let category_cell_children = &category_cell.children.borrow();
-    let category_node = category_cell_children.get(1)?;
-
-    // Extract the category from the node as a String.
-    let mut category = String::new();
-    if let html5ever::rcdom::NodeData::Element { attrs, .. } = &category_node.data {
-        attrs
-            .borrow()
-            .iter()
-            .filter(|att| &att.name.local == "title")
-            .for_each(|att| category = att.value.to_string());
-    }
+// Struct that holds the cells of a row of results by the role of their
+// column. Cells are told apart by what they contain rather than by their
+// position so that added or moved columns don't break the scraper.
+struct Cells {
+    category: Option<html5ever::rcdom::Handle>,
+    name: Option<html5ever::rcdom::Handle>,
+    links: Option<html5ever::rcdom::Handle>,
+    size: Option<html5ever::rcdom::Handle>,
+    date: Option<html5ever::rcdom::Handle>,
+    // Seeders, leechers and completed downloads: the cells after the date.
+    numbers: Vec<html5ever::rcdom::Handle>,
+}
 
-    match category.as_ref() {
-        "Anime - Anime Music Video" => Some(Category::Anime(Anime::AnimeMusicVideo)),
-        "Anime - English-translated" => Some(Category::Anime(Anime::EnglishTranslated)),
-        "Anime - Non-English-translated" => Some(Category::Anime(Anime::NonEnglishTranslated)),
-        "Anime - Raw" => Some(Category::Anime(Anime::Raw)),
-        "Audio - Lossless" => Some(Category::Audio(Audio::Lossless)),
-        "Audio - Lossy" => Some(Category::Audio(Audio::Lossy)),
-        "Literature - English-translated" => {
-            Some(Category::Literature(Literature::EnglishTranslated))
-        }
-        "Literature - Non-English-translated" => {
-            Some(Category::Literature(Literature::NonEnglishTranslated))
-        }
-        "Literature - Raw" => Some(Category::Literature(Literature::Raw)),
-        "Live Action - English-translated" => {
-            Some(Category::LiveAction(LiveAction::EnglishTranslated))
-        }
-        "Live Action - Idol/Promotional Video" => {
-            Some(Category::LiveAction(LiveAction::IdolPromotionalVideo))
+impl Cells {
+    fn new(row: &html5ever::rcdom::Handle) -> Cells {
+        let mut cells = Cells {
+            category: None,
+            name: None,
+            links: None,
+            size: None,
+            date: None,
+            numbers: vec![],
+        };
+
+        for td in element_children(row)
+            .into_iter()
+            .filter(|n| is_tag(n, "td"))
+        {
+            let has_link = |f: &dyn Fn(&str) -> bool| {
+                find(&td, &|n| {
+                    is_tag(n, "a") && get_attribute(n, "href").is_some_and(|href| f(&href))
+                })
+                .is_some()
+            };
+
+            if get_attribute(&td, "data-timestamp").is_some() {
+                cells.date = Some(td);
+            } else if cells.date.is_some() {
+                cells.numbers.push(td);
+            } else if has_link(&|href| href.starts_with("/?c=") || href.starts_with("?c=")) {
+                cells.category = Some(td);
+            } else if has_link(&|href| href.starts_with("magnet:") || href.contains("/download/")) {
+                cells.links = Some(td);
+            } else if has_link(&|href| href.contains("/view/")) {
+                cells.name = Some(td);
+            } else if looks_like_size(text_content(&td).trim()) {
+                cells.size = Some(td);
+            }
         }
-        "Live Action - Non-English-translated" => {
-            Some(Category::LiveAction(LiveAction::NonEnglishTranslated))
+
+        cells
+    }
+}
+
+// Whether a text is a number followed by a unit (e.g. "1.4 GiB"), even one
+// that is not known.
+fn looks_like_size(text: &str) -> bool {
+    let parts = text.split_whitespace().collect::<Vec<_>>();
+
+    match parts[..] {
+        [number, unit] => {
+            number.starts_with(|c: char| c.is_ascii_digit())
+                && number.chars().all(|c| c.is_ascii_digit() || c == '.')
+                && unit.chars().all(char::is_alphabetic)
         }
-        "Live Action - Raw" => Some(Category::LiveAction(LiveAction::Raw)),
-        "Pictures - Graphics" => Some(Category::Pictures(Pictures::Graphics)),
-        "Pictures - Photos" => Some(Category::Pictures(Pictures::Photos)),
-        "Software - Applications" => Some(Category::Software(Software::Applications)),
-        "Software - Games" => Some(Category::Software(Software::Games)),
-        _ => None,
+        _ => false,
     }
 }
 
-// Extract torrent links: .torrent file and magnet link.
-fn get_links(row: &html5ever::rcdom::Node) -> Option<(Option<String>, Option<String>)> {
-    let mut torrent_file = None;
-    let mut magnet_link = None;
+// Find the link to the torrent's page in the name cell: the one that is not
+// the comments badge.
+fn get_title(name_cell: &html5ever::rcdom::Handle) -> Option<html5ever::rcdom::Handle> {
+    find(name_cell, &|n| {
+        is_tag(n, "a")
+            && !has_class(n, "comments")
+            && get_attribute(n, "href").is_some_and(|href| href.contains("/view/"))
+    })
+}
 
-    // Get to the node that contains the links.
-    let row_children = &row.children.borrow();
-    let link_cell = row_children.get(5)?;
-    let link_cell_children = &link_cell.children.borrow();
-    let torrent_file_node = link_cell_children.get(1);
-    let magnet_link_node = link_cell_children.get(3);
+// Extract torrent name from a table row.
+fn get_name(cells: &Cells) -> Option<String> {
+    let title = get_title(cells.name.as_ref()?)?;
+    let name = text_content(&title).trim().to_string();
 
-    // Extract the torrent file link and the magnet link from the nodes (if they
-    // exist) as Strings.
-    match &torrent_file_node {
-        Some(node) => {
-            if let html5ever::rcdom::NodeData::Element { attrs, .. } = &node.data {
-                attrs
-                    .borrow()
-                    .iter()
-                    .filter(|att| &att.name.local == "href")
-                    .for_each(|att| torrent_file = Some(att.value.to_string()));
-            }
-        }
-        None => (),
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
     }
+}
 
-    match &magnet_link_node {
-        Some(node) => {
-            if let html5ever::rcdom::NodeData::Element { attrs, .. } = &node.data {
-                attrs
-                    .borrow()
-                    .iter()
-                    .filter(|att| &att.name.local == "href")
-                    .for_each(|att| magnet_link = Some(att.value.to_string()));
+// Extract torrent category from a table row.
+fn get_category(cells: &Cells) -> Option<Category> {
+    let category_node = find(cells.category.as_ref()?, &|n| is_tag(n, "a"))?;
+
+    // The title of the link is the category's name, which (unlike the code in
+    // the link, e.g. "/?c=1_2") tells Nyaa.si and Sukebei apart.
+    get_attribute(&category_node, "title")
+        .and_then(|title| title.parse().ok())
+        .or_else(|| {
+            let href = get_attribute(&category_node, "href")?;
+            Category::from_code(href.trim_start_matches('/').trim_start_matches("?c="))
+        })
+}
+
+// Extract the number of comments from the badge next to the title (rows of
+// torrents without comments have no badge).
+fn get_comments(cells: &Cells) -> Option<u32> {
+    match find(cells.name.as_ref()?, &|n| {
+        is_tag(n, "a") && has_class(n, "comments")
+    }) {
+        Some(badge) => text_content(&badge).trim().parse().ok(),
+        None => Some(0),
+    }
+}
+
+// Extract torrent links: .torrent file and magnet link from the links cell
+// (in whatever order they come) and the torrent's page from the title.
+fn get_links(cells: &Cells) -> Option<TorrentLinks> {
+    let mut links = TorrentLinks::default();
+
+    if let Some(link_cell) = &cells.links {
+        for href in find_all(link_cell, &|n| is_tag(n, "a"))
+            .iter()
+            .filter_map(|a| get_attribute(a, "href"))
+        {
+            if href.starts_with("magnet:") {
+                links.magnet = Some(href);
+            } else if href.contains("/download/") {
+                links.download_url = Some(href);
             }
         }
-        None => (),
     }
 
-    Some((torrent_file, magnet_link))
+    let title = get_title(cells.name.as_ref()?)?;
+    links.view_url = get_attribute(&title, "href");
+
+    Some(links)
 }
 
 // Extract torrent's date.
-fn get_date(row: &html5ever::rcdom::Node) -> Option<u64> {
-    // Get to the node that contains the date.
-    let row_children = &row.children.borrow();
-    let date_cell = row_children.get(9)?;
-
-    // Extact date as String.
-    let mut date_raw: Option<String> = None;
-    if let html5ever::rcdom::NodeData::Element { attrs, .. } = &date_cell.data {
-        attrs
-            .borrow()
-            .iter()
-            .filter(|att| &att.name.local == "data-timestamp")
-            .for_each(|att| date_raw = Some(att.value.to_string()))
-    }
+fn get_date(cells: &Cells) -> Option<Timestamp> {
+    get_attribute(cells.date.as_ref()?, "data-timestamp")?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Timestamp::from_unix)
+}
 
-    // Convert and return.
-    match date_raw {
-        Some(d) => Some(d.parse::<u64>().unwrap()),
-        None => None,
-    }
+// Extract the name (or, without one, the link) of a row's category, to tell
+// which category could not be parsed.
+fn get_category_text(cells: &Cells) -> Option<String> {
+    let category_node = find(cells.category.as_ref()?, &|n| is_tag(n, "a"))?;
+    get_attribute(&category_node, "title").or_else(|| get_attribute(&category_node, "href"))
+}
+
+// Extract the text of a cell, to tell which value could not be parsed.
+fn get_text(cell: Option<&html5ever::rcdom::Handle>) -> Option<String> {
+    Some(text_content(cell?).trim().to_string())
 }
 
 // Extract torrent's size.
-fn get_size(row: &html5ever::rcdom::Node) -> Option<u64> {
-    // Extract size as a String and split it.
-    let size_raw = get_text(row, 7)?;
-    let size_raw_split = size_raw.split(' ').collect::<Vec<_>>();
-
-    // Extract the coefficient and unit of measure.
-    let coefficient = size_raw_split.get(0)?.parse::<f64>().unwrap();
-
-    let unit_raw = size_raw_split.get(1)?;
-    let unit: i64 = match *unit_raw {
-        "Bytes" => 1,
-        "KiB" => 1_024,
-        "MiB" => 1_048_576,
-        "GiB" => 1_073_741_824,
-        "TiB" => 1_099_511_627_776,
-        _ => 0,
-    };
+fn get_size(cells: &Cells) -> Option<ByteSize> {
+    ByteSize::parse(text_content(cells.size.as_ref()?).trim())
+}
 
-    // Return torrent's size in bytes.
-    if unit == 0 {
-        None
-    } else {
-        let x = coefficient * unit as f64;
-        Some(x.round() as u64)
-    }
+// Extract the n-th number after the date: seeders, leechers and completed
+// downloads.
+fn get_number(cells: &Cells, n: usize) -> Option<u32> {
+    text_content(cells.numbers.get(n)?).trim().parse().ok()
 }
 
-// Extract torrent's seeders.
-fn get_seeders(row: &html5ever::rcdom::Node) -> Option<u32> {
-    let seeders_raw = get_text(row, 11)?;
-    let seeders = seeders_raw.parse::<u32>();
+// Identifies an Element node.
+fn is_element(n: &html5ever::rcdom::Node) -> bool {
+    matches!(n.data, html5ever::rcdom::NodeData::Element { .. })
+}
 
-    match seeders {
-        Ok(s) => Some(s),
-        Err(_) => None,
+// Find, in document order, every element under handle (handle included) that
+// satisfies f.
+pub(crate) fn find_all<F>(handle: &html5ever::rcdom::Handle, f: &F) -> Vec<html5ever::rcdom::Handle>
+where
+    F: Fn(&html5ever::rcdom::Node) -> bool,
+{
+    let mut result = vec![];
+
+    if is_element(handle) && f(handle) {
+        result.push(handle.clone());
     }
+
+    for child in handle.children.borrow().iter() {
+        result.append(&mut find_all(child, f));
+    }
+
+    result
 }
 
-// Extract torrent's leechers.
-fn get_leechers(row: &html5ever::rcdom::Node) -> Option<u32> {
-    let leechers_raw = get_text(row, 13)?;
-    let leechers = leechers_raw.parse::<u32>();
+// Find the first element under handle (handle included) that satisfies f.
+pub(crate) fn find<F>(handle: &html5ever::rcdom::Handle, f: &F) -> Option<html5ever::rcdom::Handle>
+where
+    F: Fn(&html5ever::rcdom::Node) -> bool,
+{
+    if is_element(handle) && f(handle) {
+        return Some(handle.clone());
+    }
+
+    handle
+        .children
+        .borrow()
+        .iter()
+        .find_map(|child| find(child, f))
+}
 
-    match leechers {
-        Ok(l) => Some(l),
-        Err(_) => None,
+// Identifies an element with the given tag name.
+pub(crate) fn is_tag(node: &html5ever::rcdom::Node, tag: &str) -> bool {
+    match &node.data {
+        html5ever::rcdom::NodeData::Element { name, .. } => &name.local == tag,
+        _ => false,
     }
 }
 
-// Extract torrent's completed downloads.
-fn get_completed_downloads(row: &html5ever::rcdom::Node) -> Option<u32> {
-    let completed_downloads_raw = get_text(row, 15)?;
-    let completed_downloads = completed_downloads_raw.parse::<u32>();
+// Identifies an element that has class among its classes.
+pub(crate) fn has_class(node: &html5ever::rcdom::Node, class: &str) -> bool {
+    match get_attribute(node, "class") {
+        Some(classes) => classes.split_whitespace().any(|c| c == class),
+        None => false,
+    }
+}
 
-    match completed_downloads {
-        Ok(c) => Some(c),
-        Err(_) => None,
+// Get the value of an element's attribute.
+pub(crate) fn get_attribute(node: &html5ever::rcdom::Node, attribute: &str) -> Option<String> {
+    match &node.data {
+        html5ever::rcdom::NodeData::Element { attrs, .. } => attrs
+            .borrow()
+            .iter()
+            .find(|att| &att.name.local == attribute)
+            .map(|att| att.value.to_string()),
+        _ => None,
     }
 }
 
-// Get the text in the nth cell of a row. Can be used "only" to extract size,
-// date, seeders, leechers and completed downloads.
-fn get_text(row: &html5ever::rcdom::Node, n: usize) -> Option<String> {
-    let mut result = None;
+// Concatenate all the text under a node.
+pub(crate) fn text_content(node: &html5ever::rcdom::Node) -> String {
+    let mut result = String::new();
 
-    // Get to the node that contains the wanted text.
-    let row_children = &row.children.borrow();
-    let node = row_children.get(n)?;
-    let node_children = &node.children.borrow();
-    let text = node_children.get(0)?;
+    if let html5ever::rcdom::NodeData::Text { contents } = &node.data {
+        result.push_str(&contents.borrow());
+    }
 
-    // Extract the wanted text from the node as a String.
-    if let html5ever::rcdom::NodeData::Text { contents } = &text.data {
-        result = Some(contents.borrow().to_string());
+    for child in node.children.borrow().iter() {
+        result.push_str(&text_content(child));
     }
 
     result
 }
+
+// Get the children of a node that are elements.
+pub(crate) fn element_children(node: &html5ever::rcdom::Node) -> Vec<html5ever::rcdom::Handle> {
+    node.children
+        .borrow()
+        .iter()
+        .filter(|n| is_element(n))
+        .cloned()
+        .collect()
+}
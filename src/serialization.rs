@@ -0,0 +1,105 @@
+extern crate serde;
+
+use serde::de::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::category::*;
+use crate::magnet::*;
+use crate::site::*;
+use crate::types::*;
+
+// Prefix of the codes of Sukebei's categories, which reuse Nyaa.si's codes.
+const SUKEBEI: &str = "sukebei:";
+
+// Serialize a type as a string and deserialize it by parsing one.
+macro_rules! string_serde {
+    ($name:ty, $to_string:expr, $parse:expr) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&$to_string(self))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                $parse(&s).ok_or_else(|| {
+                    D::Error::custom(format!("invalid {}: {:?}", stringify!($name), s))
+                })
+            }
+        }
+    };
+}
+
+string_serde!(Category, category_code, parse_category_code);
+string_serde!(CategoryFilter, filter_code, parse_filter_code);
+string_serde!(Anime, |c: &Anime| c.code(), Anime::from_code);
+string_serde!(Audio, |c: &Audio| c.code(), Audio::from_code);
+string_serde!(Literature, |c: &Literature| c.code(), Literature::from_code);
+string_serde!(LiveAction, |c: &LiveAction| c.code(), LiveAction::from_code);
+string_serde!(Pictures, |c: &Pictures| c.code(), Pictures::from_code);
+string_serde!(Software, |c: &Software| c.code(), Software::from_code);
+string_serde!(Art, |c: &Art| c.code(), Art::from_code);
+string_serde!(RealLife, |c: &RealLife| c.code(), RealLife::from_code);
+string_serde!(InfoHash, InfoHash::to_hex, |s: &str| s.parse().ok());
+string_serde!(Magnet, Magnet::to_uri, |s: &str| Magnet::parse(s).ok());
+
+fn category_code(category: &Category) -> String {
+    match category.site() {
+        Site::Nyaa => category.code().to_string(),
+        Site::Sukebei => format!("{}{}", SUKEBEI, category.code()),
+    }
+}
+
+fn parse_category_code(code: &str) -> Option<Category> {
+    match code.strip_prefix(SUKEBEI) {
+        Some(code) => Category::from_site_code(Site::Sukebei, code),
+        None => Category::from_code(code),
+    }
+}
+
+fn filter_code(filter: &CategoryFilter) -> String {
+    let site = match filter {
+        CategoryFilter::Art | CategoryFilter::RealLife => Site::Sukebei,
+        CategoryFilter::Only(category) => category.site(),
+        _ => Site::Nyaa,
+    };
+
+    match site {
+        Site::Nyaa => filter.code().to_string(),
+        Site::Sukebei => format!("{}{}", SUKEBEI, filter.code()),
+    }
+}
+
+fn parse_filter_code(code: &str) -> Option<CategoryFilter> {
+    match code.strip_prefix(SUKEBEI) {
+        Some(code) => CategoryFilter::from_site_code(Site::Sukebei, code),
+        None => CategoryFilter::from_code(code),
+    }
+}
+
+// Errors can't be deserialized since they wrap errors of other crates: they
+// are serialized as their kind and message, plus the HTTP status for status
+// errors.
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let kind = match self {
+            Error::Request(_) => "request",
+            Error::Timeout(_) => "timeout",
+            Error::Status(_) => "status",
+            Error::Io(_) => "io",
+            Error::Configuration(_) => "configuration",
+            Error::Scraping(_) => "scraping",
+        };
+
+        let fields = if let Error::Status(_) = self { 3 } else { 2 };
+        let mut error = serializer.serialize_struct("Error", fields)?;
+        error.serialize_field("kind", kind)?;
+        error.serialize_field("message", &self.to_string())?;
+        if let Error::Status(status) = self {
+            error.serialize_field("status", status)?;
+        }
+        error.end()
+    }
+}
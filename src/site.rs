@@ -0,0 +1,106 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::category::*;
+
+// Trackers of the magnet links of each site: their own and some open ones.
+const NYAA_TRACKERS: [&str; 5] = [
+    "http://nyaa.tracker.wf:7777/announce",
+    "udp://open.stealth.si:80/announce",
+    "udp://tracker.opentrackr.org:1337/announce",
+    "udp://exodus.desync.com:6969/announce",
+    "udp://tracker.torrent.eu.org:451/announce",
+];
+const SUKEBEI_TRACKERS: [&str; 5] = [
+    "http://sukebei.tracker.wf:8888/announce",
+    "udp://open.stealth.si:80/announce",
+    "udp://tracker.opentrackr.org:1337/announce",
+    "udp://exodus.desync.com:6969/announce",
+    "udp://tracker.torrent.eu.org:451/announce",
+];
+
+/// Enum that encodes the sites running Nyaa's software that this library
+/// knows about. They share their pages but not their categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Site {
+    /// <https://nyaa.si>
+    Nyaa,
+    /// <https://sukebei.nyaa.si>
+    Sukebei,
+}
+
+impl Site {
+    /// Url of the site.
+    pub fn base_url(self) -> &'static str {
+        match self {
+            Site::Nyaa => "https://nyaa.si",
+            Site::Sukebei => "https://sukebei.nyaa.si",
+        }
+    }
+
+    /// Site whose url is the given one, or that the url is a mirror of: urls
+    /// whose host starts with `sukebei.` are Sukebei's, everything else is
+    /// Nyaa.si's.
+    pub fn from_base_url(url: &str) -> Site {
+        let host = url.split("://").last().unwrap_or(url);
+        if host.starts_with("sukebei.") {
+            Site::Sukebei
+        } else {
+            Site::Nyaa
+        }
+    }
+
+    /// Trackers the site puts in its magnet links.
+    pub fn trackers(self) -> &'static [&'static str] {
+        match self {
+            Site::Nyaa => &NYAA_TRACKERS,
+            Site::Sukebei => &SUKEBEI_TRACKERS,
+        }
+    }
+
+    /// Iterate over the categories of the site.
+    pub fn categories(self) -> Box<dyn Iterator<Item = Category>> {
+        match self {
+            Site::Nyaa => Box::new(
+                Anime::all()
+                    .map(Category::from)
+                    .chain(Audio::all().map(Category::from))
+                    .chain(Literature::all().map(Category::from))
+                    .chain(LiveAction::all().map(Category::from))
+                    .chain(Pictures::all().map(Category::from))
+                    .chain(Software::all().map(Category::from)),
+            ),
+            Site::Sukebei => Box::new(
+                Art::all()
+                    .map(Category::from)
+                    .chain(RealLife::all().map(Category::from)),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Site {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Site::Nyaa => write!(f, "nyaa"),
+            Site::Sukebei => write!(f, "sukebei"),
+        }
+    }
+}
+
+impl FromStr for Site {
+    type Err = String;
+
+    /// Parse "nyaa" or "sukebei" (case insensitive).
+    fn from_str(s: &str) -> Result<Site, String> {
+        if s.eq_ignore_ascii_case("nyaa") {
+            Ok(Site::Nyaa)
+        } else if s.eq_ignore_ascii_case("sukebei") {
+            Ok(Site::Sukebei)
+        } else {
+            Err(format!("unknown site: {:?}", s))
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+// Units Nyaa.si uses for sizes, with their number of bytes.
+const UNITS: [(&str, u64); 7] = [
+    ("Bytes", 1),
+    ("KiB", 1 << 10),
+    ("MiB", 1 << 20),
+    ("GiB", 1 << 30),
+    ("TiB", 1 << 40),
+    ("PiB", 1 << 50),
+    ("EiB", 1 << 60),
+];
+
+/// Struct that represents a size in bytes.
+///
+/// Sizes are parsed exactly from the way Nyaa.si writes them (e.g. "1.4 GiB")
+/// and they are displayed the same way:
+///
+/// ```
+/// use nyaadesu::ByteSize;
+///
+/// let size = ByteSize::parse("1.4 GiB").unwrap();
+/// assert_eq!(size.bytes(), 1_503_238_554);
+/// assert_eq!(size.to_string(), "1.4 GiB");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Number of bytes.
+    pub fn bytes(self) -> u64 {
+        self.0
+    }
+
+    /// Parse a size written as a decimal number and a binary unit (`Bytes`,
+    /// `B`, `KiB`, `MiB`, `GiB`, `TiB`, `PiB` or `EiB`). The result is rounded
+    /// to the nearest byte. Returns `None` if the size can't be parsed or if
+    /// it does not fit in 64 bits.
+    pub fn parse(size: &str) -> Option<ByteSize> {
+        let mut parts = size.split_whitespace();
+        let number = parts.next()?;
+        let unit = parts.next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let unit = match unit {
+            "B" => 1,
+            _ => UNITS.iter().find(|(name, _)| *name == unit)?.1,
+        };
+
+        // Compute (integer + fraction / 10^digits) * unit with integers.
+        let (integer, fraction) = match number.find('.') {
+            Some(i) => (&number[..i], &number[i + 1..]),
+            None => (number, ""),
+        };
+        if integer.is_empty()
+            || !integer.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+            || fraction.len() > 18
+        {
+            return None;
+        }
+
+        let scale = 10u128.pow(fraction.len() as u32);
+        let integer = integer.parse::<u128>().ok()?;
+        let fraction = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u128>().ok()?
+        };
+
+        let scaled = integer
+            .checked_mul(scale)?
+            .checked_add(fraction)?
+            .checked_mul(u128::from(unit))?;
+        let bytes = (scaled + scale / 2) / scale;
+
+        if bytes > u128::from(u64::MAX) {
+            None
+        } else {
+            Some(ByteSize(bytes as u64))
+        }
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Format the size like Nyaa.si does: with the largest unit that keeps
+    /// the number above 1 and one decimal digit (e.g. "350.2 MiB").
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, unit) = UNITS
+            .iter()
+            .rev()
+            .find(|(_, unit)| self.0 >= *unit)
+            .unwrap_or(&UNITS[0]);
+
+        if *unit == 1 {
+            return write!(f, "{} Bytes", self.0);
+        }
+
+        // Tenths of the unit, rounded to the nearest.
+        let tenths = (u128::from(self.0) * 10 + u128::from(*unit) / 2) / u128::from(*unit);
+        write!(f, "{}.{} {}", tenths / 10, tenths % 10, name)
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> ByteSize {
+        ByteSize(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> u64 {
+        size.0
+    }
+}
@@ -0,0 +1,1058 @@
+//! Fake Nyaa.si for tests, enabled by the `testing` feature.
+//!
+//! [`FakeNyaa`] answers search, view, RSS and download requests like Nyaa.si
+//! does, from a list of [`Torrent`]s, on a local port. Point a client at it
+//! with [`NyaaClientBuilder::base_url`]:
+//!
+//! ```
+//! # #[cfg(feature = "blocking")]
+//! # fn main() {
+//! use nyaadesu::testing::{self, FakeNyaa};
+//! use nyaadesu::*;
+//!
+//! let nyaa = FakeNyaa::new(vec![testing::torrent(1, "Little Witch Academia")]).start();
+//! let client = NyaaClient::builder().base_url(&nyaa.url).build().unwrap();
+//!
+//! let torrents = client.search(&SearchQuery::new("witch")).unwrap();
+//! assert_eq!(torrents[0].name, "Little Witch Academia");
+//! # }
+//! # #[cfg(not(feature = "blocking"))]
+//! # fn main() {}
+//! ```
+//!
+//! The pages are rendered with the same markup as Nyaa.si's so they go
+//! through the real parsers. Sizes are written the way Nyaa.si writes them
+//! (e.g. "1.4 GiB") so they come back rounded to that precision.
+//!
+//! [`FakeNyaa`]: ./struct.FakeNyaa.html
+//! [`Torrent`]: ../struct.Torrent.html
+//! [`NyaaClientBuilder::base_url`]: ../struct.NyaaClientBuilder.html#method.base_url
+
+extern crate percent_encoding;
+extern crate sha1_smol;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::category::*;
+use crate::magnet::*;
+use crate::query::*;
+use crate::scraping::*;
+use crate::site::*;
+use crate::size::*;
+use crate::timestamp::*;
+use crate::types::*;
+
+// Trackers of the magnet links and .torrent files made up for torrents.
+const TRACKER: &str = "http://nyaa.tracker.wf:7777/announce";
+
+/// Struct that represents a local HTTP server started by [`serve`] or
+/// [`FakeNyaa::start`]. It keeps answering until the process exits.
+///
+/// [`serve`]: ./fn.serve.html
+/// [`FakeNyaa::start`]: ./struct.FakeNyaa.html#method.start
+#[derive(Debug)]
+pub struct FakeServer {
+    /// Url of the server (e.g. `http://127.0.0.1:34567`), without a trailing
+    /// slash.
+    pub url: String,
+    requests: Arc<AtomicUsize>,
+}
+
+impl FakeServer {
+    /// Number of requests answered so far.
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+/// Start a server on a free local port that answers each request with the
+/// body returned by `route` for the requested path (e.g. `/view/1?x=y`), or
+/// with a 404 when it returns `None`.
+pub fn serve<F>(route: F) -> FakeServer
+where
+    F: Fn(&str) -> Option<Vec<u8>> + Send + 'static,
+{
+    serve_responses(move |path| Response::from(route(path)))
+}
+
+/// Start a server on a free local port that answers each request with the
+/// [`Response`] returned by `route` for the requested path. This can answer
+/// with any status and headers, e.g. to throttle a client:
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use nyaadesu::testing::{self, FakeNyaa, Response};
+///
+/// let nyaa = FakeNyaa::new(vec![testing::torrent(1, "Little Witch Academia")]);
+/// let tries = AtomicUsize::new(0);
+/// let server = testing::serve_responses(move |path| {
+///     if tries.fetch_add(1, Ordering::SeqCst) == 0 {
+///         Response::status(429).header("Retry-After", "1")
+///     } else {
+///         Response::from(nyaa.respond("http://127.0.0.1", path))
+///     }
+/// });
+/// ```
+///
+/// [`Response`]: ./struct.Response.html
+pub fn serve_responses<F>(route: F) -> FakeServer
+where
+    F: Fn(&str) -> Response + Send + 'static,
+{
+    let (listener, url) = bind();
+    serve_on(listener, url, route)
+}
+
+/// Struct that represents an answer of a server started by
+/// [`serve_responses`].
+///
+/// [`serve_responses`]: ./fn.serve_responses.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// Status code (e.g. 200 or 429).
+    pub status: u16,
+    /// Headers besides `Content-Length` and `Connection`.
+    pub headers: Vec<(String, String)>,
+    /// Body of the answer.
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Answer 200 with the given body.
+    pub fn ok(body: Vec<u8>) -> Response {
+        Response {
+            status: 200,
+            headers: vec![],
+            body,
+        }
+    }
+
+    /// Answer the given status with an empty body.
+    pub fn status(status: u16) -> Response {
+        Response {
+            status,
+            headers: vec![],
+            body: vec![],
+        }
+    }
+
+    /// Add a header to the answer.
+    pub fn header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl From<Option<Vec<u8>>> for Response {
+    /// Answer 200 with the body, or 404 without one, like [`serve`] does.
+    ///
+    /// [`serve`]: ./fn.serve.html
+    fn from(body: Option<Vec<u8>>) -> Response {
+        match body {
+            Some(body) => Response::ok(body),
+            None => Response::status(404),
+        }
+    }
+}
+
+// Listen on a free local port.
+fn bind() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("can't listen on a local port");
+    let url = format!(
+        "http://{}",
+        listener.local_addr().expect("listeners have an address")
+    );
+
+    (listener, url)
+}
+
+// Answer the requests to listener with route, one at a time.
+fn serve_on<F>(listener: TcpListener, url: String, route: F) -> FakeServer
+where
+    F: Fn(&str) -> Response + Send + 'static,
+{
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+            let mut reader = match stream.try_clone() {
+                Ok(stream) => BufReader::new(stream),
+                Err(_) => continue,
+            };
+
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            loop {
+                let mut header = String::new();
+                match reader.read_line(&mut header) {
+                    Ok(n) if n > 2 => (),
+                    _ => break,
+                }
+            }
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+            let response = route(path);
+            let mut head = format!(
+                "HTTP/1.1 {} {}\r\n",
+                response.status,
+                reason(response.status)
+            );
+            for (name, value) in response.headers.iter() {
+                head.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            let _ = write!(
+                stream,
+                "{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                head,
+                response.body.len()
+            );
+            let _ = stream.write_all(&response.body);
+        }
+    });
+
+    FakeServer { url, requests }
+}
+
+// Reason phrase of a status, which clients don't read.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+/// Struct that represents a fake Nyaa.si (or Sukebei) holding some torrents.
+///
+/// It answers like the real site:
+///
+/// - `/?q=...&c=...&f=...&s=...&o=...&p=...` with a page of results: the
+///   torrents whose names contain every word of `q` (ignoring case), in the
+///   category `c`, filtered by `f`, sorted by `s` in the order `o`;
+/// - `/?page=rss&...` with the RSS feed of the first page of the same search;
+/// - `/view/<id>` with the torrent's page;
+/// - `/download/<id>.torrent` with its .torrent file;
+/// - anything else (including pages past the last one) with a 404.
+#[derive(Debug, Clone)]
+pub struct FakeNyaa {
+    site: Site,
+    torrents: Vec<Torrent>,
+    details: Vec<TorrentDetails>,
+    torrent_files: Vec<(u32, Vec<u8>)>,
+    page_size: usize,
+}
+
+impl FakeNyaa {
+    /// Create a fake Nyaa.si holding the given torrents. Their magnet links
+    /// are listed as they are (torrents without one are listed without one)
+    /// while the other links are replaced by the ones of the fake site.
+    pub fn new(torrents: Vec<Torrent>) -> FakeNyaa {
+        FakeNyaa {
+            site: Site::Nyaa,
+            torrents,
+            details: vec![],
+            torrent_files: vec![],
+            page_size: 75,
+        }
+    }
+
+    /// Pretend to be the given site. This only changes how the `c` query
+    /// parameter is read.
+    pub fn site(mut self, site: Site) -> FakeNyaa {
+        self.site = site;
+        self
+    }
+
+    /// Set the number of results in a page (75 by default, like Nyaa.si).
+    pub fn page_size(mut self, page_size: usize) -> FakeNyaa {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Set what the page of a torrent shows. The torrent is added to the site
+    /// if it is not there yet. Without this the page of a torrent shows an
+    /// anonymous submitter, no description, comments or information and a
+    /// single file.
+    pub fn details(mut self, details: TorrentDetails) -> FakeNyaa {
+        if !self.torrents.iter().any(|t| t.id == details.torrent.id) {
+            self.torrents.push(details.torrent.clone());
+        }
+        self.details.retain(|d| d.torrent.id != details.torrent.id);
+        self.details.push(details);
+        self
+    }
+
+    /// Set the .torrent file of a torrent. Without this a .torrent file is
+    /// made up by [`render_torrent_file`].
+    ///
+    /// [`render_torrent_file`]: ./fn.render_torrent_file.html
+    pub fn torrent_file(mut self, id: u32, bytes: Vec<u8>) -> FakeNyaa {
+        self.torrent_files.retain(|(i, _)| *i != id);
+        self.torrent_files.push((id, bytes));
+        self
+    }
+
+    /// Start serving the site on a free local port.
+    pub fn start(self) -> FakeServer {
+        let (listener, url) = bind();
+        let base_url = url.clone();
+
+        serve_on(listener, url, move |path| {
+            Response::from(self.respond(&base_url, path))
+        })
+    }
+
+    /// Answer a request for `path` (e.g. `/?q=witch&p=2`) like [`start`]ed
+    /// servers do, with `base_url` as the site's url. Returns `None` for a
+    /// 404.
+    ///
+    /// [`start`]: #method.start
+    pub fn respond(&self, base_url: &str, path: &str) -> Option<Vec<u8>> {
+        if let Some(rest) = path.strip_prefix("/view/") {
+            let id = rest
+                .split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse::<u32>()
+                .ok()?;
+            let details = self.torrent_details(id)?;
+
+            Some(render_view_page(&details).into_bytes())
+        } else if let Some(rest) = path.strip_prefix("/download/") {
+            let id = rest.strip_suffix(".torrent")?.parse::<u32>().ok()?;
+            match self.torrent_files.iter().find(|(i, _)| *i == id) {
+                Some((_, bytes)) => Some(bytes.clone()),
+                None => Some(render_torrent_file(self.torrent(id)?)),
+            }
+        } else if path == "/" || path.starts_with("/?") {
+            let params = query_params(path);
+            let param = |name| param(&params, name);
+
+            let results = self.search(&params);
+            if param("page") == Some("rss") {
+                let results = &results[..results.len().min(self.page_size)];
+                return Some(render_rss(&self.listed(results), base_url).into_bytes());
+            }
+
+            let page = match param("p") {
+                Some(p) => p.parse::<usize>().ok().filter(|p| *p > 0)?,
+                None => 1,
+            };
+            if results.is_empty() {
+                return match page {
+                    1 => Some(render_search_page(&[], None).into_bytes()),
+                    _ => None,
+                };
+            }
+
+            let start = (page - 1).checked_mul(self.page_size)?;
+            if start >= results.len() {
+                return None;
+            }
+            let end = (start + self.page_size).min(results.len());
+            let meta = SearchMeta {
+                total_results: results.len() as u32,
+                page_size: self.page_size as u32,
+                current_page: page as u32,
+                last_page: ((results.len() - 1) / self.page_size + 1) as u32,
+            };
+
+            Some(render_search_page(&self.listed(&results[start..end]), Some(meta)).into_bytes())
+        } else {
+            None
+        }
+    }
+
+    // Torrents matching the search in the query parameters, sorted.
+    fn search(&self, params: &[(String, String)]) -> Vec<&Torrent> {
+        let param = |name| param(params, name);
+        let terms = param("q")
+            .unwrap_or("")
+            .split_whitespace()
+            .map(|t| t.to_lowercase())
+            .collect::<Vec<_>>();
+        let category = param("c")
+            .and_then(|c| CategoryFilter::from_site_code(self.site, c))
+            .unwrap_or(CategoryFilter::All);
+        let filter = match param("f") {
+            Some("1") => Filter::NoRemakes,
+            Some("2") => Filter::TrustedOnly,
+            _ => Filter::NoFilter,
+        };
+
+        let mut results = self
+            .torrents
+            .iter()
+            .filter(|t| {
+                let name = t.name.to_lowercase();
+                terms.iter().all(|term| name.contains(term.as_str()))
+            })
+            .filter(|t| category.contains(t.category))
+            .filter(|t| match filter {
+                Filter::NoFilter => true,
+                Filter::NoRemakes => t.status != Status::Remake,
+                Filter::TrustedOnly => t.status == Status::Trusted,
+            })
+            .collect::<Vec<_>>();
+
+        // Nyaa.si sorts by id (i.e. by date) when no key is given.
+        results.sort_by(|a, b| {
+            let key = match param("s") {
+                Some("size") => a.size.cmp(&b.size),
+                Some("seeders") => a.seeders.cmp(&b.seeders),
+                Some("leechers") => a.leechers.cmp(&b.leechers),
+                Some("downloads") => a.completed_downloads.cmp(&b.completed_downloads),
+                Some("comments") => a.comments.cmp(&b.comments),
+                _ => std::cmp::Ordering::Equal,
+            };
+            key.then(a.id.cmp(&b.id))
+        });
+        if param("o") != Some("asc") {
+            results.reverse();
+        }
+
+        results
+    }
+
+    // Torrents with the links of the fake site.
+    fn listed(&self, torrents: &[&Torrent]) -> Vec<Torrent> {
+        torrents
+            .iter()
+            .map(|t| {
+                let mut torrent = (*t).clone();
+                torrent.links = TorrentLinks {
+                    download_url: Some(format!("/download/{}.torrent", t.id)),
+                    magnet: t.links.magnet.clone(),
+                    view_url: Some(format!("/view/{}", t.id)),
+                };
+                torrent
+            })
+            .collect()
+    }
+
+    // The torrent with the given id.
+    fn torrent(&self, id: u32) -> Option<&Torrent> {
+        self.torrents.iter().find(|t| t.id == id)
+    }
+
+    // What the page of the torrent with the given id shows.
+    fn torrent_details(&self, id: u32) -> Option<TorrentDetails> {
+        let mut details = match self.details.iter().find(|d| d.torrent.id == id) {
+            Some(details) => details.clone(),
+            None => {
+                let torrent = self.torrent(id)?;
+                TorrentDetails {
+                    torrent: torrent.clone(),
+                    submitter: None,
+                    information: None,
+                    info_hash: info_hash(torrent),
+                    description: String::new(),
+                    files: vec![FileEntry::File {
+                        name: torrent.name.clone(),
+                        size: torrent.size,
+                    }],
+                    comments: vec![],
+                }
+            }
+        };
+
+        let torrent = details.torrent.clone();
+        details.torrent = self.listed(&[&torrent]).remove(0);
+        Some(details)
+    }
+}
+
+/// Create a torrent to put in a [`FakeNyaa`]: an English-translated anime
+/// with the given id and name, a magnet link and made up numbers.
+///
+/// [`FakeNyaa`]: ./struct.FakeNyaa.html
+pub fn torrent(id: u32, name: &str) -> Torrent {
+    let mut torrent = Torrent {
+        id,
+        category: Category::Anime(Anime::EnglishTranslated),
+        name: name.to_string(),
+        links: TorrentLinks::default(),
+        size: ByteSize(u64::from(id % 1000 + 1) << 20),
+        date: Timestamp::from_unix(1_600_000_000 + u64::from(id) * 60),
+        seeders: id % 100,
+        leechers: id % 10,
+        completed_downloads: id % 1000,
+        comments: 0,
+        status: Status::Normal,
+    };
+    torrent.links = TorrentLinks {
+        download_url: Some(format!("/download/{}.torrent", id)),
+        magnet: Some(magnet_link(&torrent)),
+        view_url: Some(format!("/view/{}", id)),
+    };
+
+    torrent
+}
+
+// Info hash of a torrent: the one of its magnet link or, for torrents
+// without one, a hash made up from its id.
+fn info_hash(torrent: &Torrent) -> InfoHash {
+    match torrent.magnet() {
+        Some(magnet) => magnet.info_hash,
+        None => InfoHash::from_bytes(
+            sha1_smol::Sha1::from(torrent.id.to_string())
+                .digest()
+                .bytes(),
+        ),
+    }
+}
+
+// Magnet link of a torrent, the way Nyaa.si writes them.
+fn magnet_link(torrent: &Torrent) -> String {
+    let mut magnet = Magnet::new(info_hash(torrent));
+    magnet.name = Some(torrent.name.clone());
+    magnet.trackers = vec![TRACKER.to_string()];
+
+    magnet.to_uri()
+}
+
+// Value of the query parameter with the given name.
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// Decoded query parameters of a path (e.g. "/?q=little+witch&p=2").
+fn query_params(path: &str) -> Vec<(String, String)> {
+    let query = match path.find('?') {
+        Some(i) => &path[i + 1..],
+        None => "",
+    };
+    let decode = |s: &str| {
+        percent_encoding::percent_decode_str(&s.replace('+', " "))
+            .decode_utf8_lossy()
+            .into_owned()
+    };
+
+    query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.find('=') {
+            Some(i) => (decode(&p[..i]), decode(&p[i + 1..])),
+            None => (decode(p), String::new()),
+        })
+        .collect()
+}
+
+/// Render a page of search results, with the result count and the
+/// pagination widget if `meta` is given. Without torrents this is the page
+/// Nyaa.si shows when nothing was found.
+///
+/// The links of the torrents are written as they are: give relative ones
+/// (e.g. `/view/1`) to have them resolved by the client.
+pub fn render_search_page(torrents: &[Torrent], meta: Option<SearchMeta>) -> String {
+    let mut html = String::new();
+    html.push_str(&page_head("Browse"));
+
+    if torrents.is_empty() && meta.is_none() {
+        html.push_str(concat!(
+            "\t\t\t<h3>No results found</h3>\n",
+            "\t\t</div>\n",
+            "\t</body>\n",
+            "</html>\n"
+        ));
+        return html;
+    }
+
+    html.push_str(concat!(
+        "\t\t\t<div class=\"table-responsive\">\n",
+        "\t\t\t\t<table class=\"table table-bordered table-hover table-striped torrent-list\">\n",
+        "\t\t\t\t\t<thead>\n",
+        "\t\t\t\t\t\t<tr>\n",
+        "\t\t\t\t\t\t\t<th class=\"hdr-category text-center\" style=\"width:80px;\">Category</th>\n",
+        "\t\t\t\t\t\t\t<th class=\"hdr-name\" style=\"width:auto;\">Name</th>\n",
+        "\t\t\t\t\t\t</tr>\n",
+        "\t\t\t\t\t</thead>\n",
+        "\t\t\t\t\t<tbody>\n",
+    ));
+    for torrent in torrents {
+        html.push_str(&render_row(torrent));
+    }
+    html.push_str(concat!(
+        "\t\t\t\t\t</tbody>\n",
+        "\t\t\t\t</table>\n",
+        "\t\t\t</div>\n",
+    ));
+
+    if let Some(meta) = meta {
+        let start = (meta.current_page - 1).saturating_mul(meta.page_size);
+        html.push_str(&format!(
+            concat!(
+                "\t\t\t<div class=\"pagination-page-info\">Displaying results {}-{} out of {} results.<br>\n",
+                "Please refine your search results if you can't find what you were looking for.</div>\n",
+                "\t\t\t<div class=\"center\">\n",
+                "\t\t\t\t<nav>\n",
+                "\t\t\t\t\t<ul class=\"pagination\">\n",
+                "\t\t\t\t\t\t<li class=\"disabled\"><a href=\"#\">&laquo;</a></li>\n",
+            ),
+            start.saturating_add(1).min(meta.total_results),
+            start.saturating_add(torrents.len() as u32),
+            meta.total_results
+        ));
+        for page in 1..=meta.last_page {
+            if page == meta.current_page {
+                html.push_str(&format!("\t\t\t\t\t\t<li class=\"active\"><a href=\"#\">{} <span class=\"sr-only\">(current)</span></a></li>\n", page));
+            } else {
+                html.push_str(&format!(
+                    "\t\t\t\t\t\t<li><a href=\"/?p={}\">{}</a></li>\n",
+                    page, page
+                ));
+            }
+        }
+        html.push_str(concat!(
+            "\t\t\t\t\t\t<li class=\"disabled\"><a href=\"#\">&raquo;</a></li>\n",
+            "\t\t\t\t\t</ul>\n",
+            "\t\t\t\t</nav>\n",
+            "\t\t\t</div>\n",
+        ));
+    }
+
+    html.push_str(page_foot());
+    html
+}
+
+// Row of the table of search results.
+fn render_row(torrent: &Torrent) -> String {
+    let class = match torrent.status {
+        Status::Trusted => "success",
+        Status::Remake => "danger",
+        Status::Normal => "default",
+    };
+    let view = torrent.links.view_url.as_deref().unwrap_or("");
+    let comments = if torrent.comments > 0 {
+        format!(
+            concat!(
+                "\t\t\t\t\t\t\t\t<a href=\"{}#comments\" class=\"comments\" title=\"{} comments\">\n",
+                "\t\t\t\t\t\t\t\t\t<i class=\"fa fa-comments-o\"></i>{}</a>\n",
+            ),
+            escape(view),
+            torrent.comments,
+            torrent.comments
+        )
+    } else {
+        String::new()
+    };
+    let mut links = String::new();
+    if let Some(download) = &torrent.links.download_url {
+        links.push_str(&format!(
+            "\t\t\t\t\t\t\t\t<a href=\"{}\"><i class=\"fa fa-fw fa-download\"></i></a>\n",
+            escape(download)
+        ));
+    }
+    if let Some(magnet) = &torrent.links.magnet {
+        links.push_str(&format!(
+            "\t\t\t\t\t\t\t\t<a href=\"{}\"><i class=\"fa fa-fw fa-magnet\"></i></a>\n",
+            escape(magnet)
+        ));
+    }
+
+    format!(
+        concat!(
+            "\t\t\t\t\t\t<tr class=\"{class}\">\n",
+            "\t\t\t\t\t\t\t<td>\n",
+            "\t\t\t\t\t\t\t\t<a href=\"/?c={code}\" title=\"{category}\">\n",
+            "\t\t\t\t\t\t\t\t\t<img src=\"/static/img/icons/{site}/{code}.png\" alt=\"{category}\" class=\"category-icon\">\n",
+            "\t\t\t\t\t\t\t\t</a>\n",
+            "\t\t\t\t\t\t\t</td>\n",
+            "\t\t\t\t\t\t\t<td colspan=\"2\">\n",
+            "{comments}",
+            "\t\t\t\t\t\t\t\t<a href=\"{view}\" title=\"{name}\">{name}</a>\n",
+            "\t\t\t\t\t\t\t</td>\n",
+            "\t\t\t\t\t\t\t<td class=\"text-center\">\n",
+            "{links}",
+            "\t\t\t\t\t\t\t</td>\n",
+            "\t\t\t\t\t\t\t<td class=\"text-center\">{size}</td>\n",
+            "\t\t\t\t\t\t\t<td class=\"text-center\" data-timestamp=\"{timestamp}\">{date}</td>\n",
+            "\t\t\t\t\t\t\t<td class=\"text-center\">{seeders}</td>\n",
+            "\t\t\t\t\t\t\t<td class=\"text-center\">{leechers}</td>\n",
+            "\t\t\t\t\t\t\t<td class=\"text-center\">{completed}</td>\n",
+            "\t\t\t\t\t\t</tr>\n",
+        ),
+        class = class,
+        code = torrent.category.code(),
+        category = torrent.category,
+        site = torrent.category.site(),
+        comments = comments,
+        view = escape(view),
+        name = escape(&torrent.name),
+        links = links,
+        size = torrent.size,
+        timestamp = torrent.date.unix(),
+        date = display_date(torrent.date),
+        seeders = torrent.seeders,
+        leechers = torrent.leechers,
+        completed = torrent.completed_downloads,
+    )
+}
+
+/// Render the page of a torrent. Its links to the .torrent file and to the
+/// page itself are always the ones of the site (`/download/<id>.torrent`,
+/// `/view/<id>`), like on Nyaa.si.
+pub fn render_view_page(details: &TorrentDetails) -> String {
+    let torrent = &details.torrent;
+    let panel = match torrent.status {
+        Status::Trusted => "panel-success",
+        Status::Remake => "panel-danger",
+        Status::Normal => "panel-default",
+    };
+    let submitter = match &details.submitter {
+        Some(submitter) => format!(
+            "<a class=\"text-default\" href=\"/user/{}\" data-toggle=\"tooltip\" title=\"User\">{}</a>",
+            escape(submitter),
+            escape(submitter)
+        ),
+        None => "Anonymous".to_string(),
+    };
+    let information = match &details.information {
+        Some(information) => format!(
+            "<a href=\"{}\">{}</a>",
+            escape(information),
+            escape(information)
+        ),
+        None => "No information.".to_string(),
+    };
+    let magnet = match &torrent.links.magnet {
+        Some(magnet) => format!(
+            " or <a href=\"{}\" class=\"card-footer-item\"><i class=\"fa fa-magnet fa-fw\"></i>Magnet</a>",
+            escape(magnet)
+        ),
+        None => String::new(),
+    };
+
+    let mut html = page_head(&escape(&torrent.name));
+    html.push_str(&format!(
+        concat!(
+            "<div class=\"panel {panel}\">\n",
+            "\t<div class=\"panel-heading\">\n",
+            "\t\t<h3 class=\"panel-title\">\n",
+            "\t\t\t{name}\n",
+            "\t\t</h3>\n",
+            "\t</div>\n",
+            "\t<div class=\"panel-body\">\n",
+            "\t\t<div class=\"row\">\n",
+            "\t\t\t<div class=\"col-md-1\">Category:</div>\n",
+            "\t\t\t<div class=\"col-md-5\">\n",
+            "\t\t\t\t<a href=\"/?c={top_code}\">{top_name}</a> - <a href=\"/?c={code}\">{category}</a>\n",
+            "\t\t\t</div>\n",
+            "\n",
+            "\t\t\t<div class=\"col-md-1\">Date:</div>\n",
+            "\t\t\t<div class=\"col-md-5\" data-timestamp=\"{timestamp}\">{date} UTC</div>\n",
+            "\t\t</div>\n",
+            "\n",
+            "\t\t<div class=\"row\">\n",
+            "\t\t\t<div class=\"col-md-1\">Submitter:</div>\n",
+            "\t\t\t<div class=\"col-md-5\">\n",
+            "\t\t\t\t{submitter}\n",
+            "\t\t\t</div>\n",
+            "\n",
+            "\t\t\t<div class=\"col-md-1\">Seeders:</div>\n",
+            "\t\t\t<div class=\"col-md-5\"><span style=\"color: green;\">{seeders}</span></div>\n",
+            "\t\t</div>\n",
+            "\n",
+            "\t\t<div class=\"row\">\n",
+            "\t\t\t<div class=\"col-md-1\">Information:</div>\n",
+            "\t\t\t<div class=\"col-md-5\">\n",
+            "\t\t\t\t{information}\n",
+            "\t\t\t</div>\n",
+            "\n",
+            "\t\t\t<div class=\"col-md-1\">Leechers:</div>\n",
+            "\t\t\t<div class=\"col-md-5\"><span style=\"color: red;\">{leechers}</span></div>\n",
+            "\t\t</div>\n",
+            "\n",
+            "\t\t<div class=\"row\">\n",
+            "\t\t\t<div class=\"col-md-1\">File size:</div>\n",
+            "\t\t\t<div class=\"col-md-5\">{size}</div>\n",
+            "\n",
+            "\t\t\t<div class=\"col-md-1\">Completed:</div>\n",
+            "\t\t\t<div class=\"col-md-5\">{completed}</div>\n",
+            "\t\t</div>\n",
+            "\n",
+            "\t\t<div class=\"row\">\n",
+            "\t\t\t<div class=\"col-md-offset-6 col-md-1\">Info hash:</div>\n",
+            "\t\t\t<div class=\"col-md-5\"><kbd>{info_hash}</kbd></div>\n",
+            "\t\t</div>\n",
+            "\t</div><!--/.panel-body -->\n",
+            "\n",
+            "\t<div class=\"panel-footer clearfix\">\n",
+            "\t\t<a href=\"/download/{id}.torrent\"><i class=\"fa fa-download fa-fw\"></i>Download Torrent</a>{magnet}\n",
+            "\t</div>\n",
+            "</div>\n",
+            "\n",
+            "<div class=\"panel panel-default\">\n",
+            "\t<div markdown-text class=\"panel-body\" id=\"torrent-description\">{description}</div>\n",
+            "</div>\n",
+            "\n",
+            "<div class=\"panel panel-default\">\n",
+            "\t<div class=\"panel-heading\">\n",
+            "\t\t<h3 class=\"panel-title\">File list</h3>\n",
+            "\t</div>\n",
+            "\t<div class=\"torrent-file-list panel-body\">\n",
+            "\t\t<ul>\n",
+            "{files}",
+            "\t\t</ul>\n",
+            "\t</div>\n",
+            "</div>\n",
+            "\n",
+            "<div id=\"comments\" class=\"panel panel-default\">\n",
+            "\t<div class=\"panel-heading\">\n",
+            "\t\t<a class=\"toggle-comments\" href=\"#collapse-comments\" data-toggle=\"collapse\">\n",
+            "\t\t\t<h3 class=\"panel-title\">Comments - {comment_count}</h3>\n",
+            "\t\t</a>\n",
+            "\t</div>\n",
+            "\t<div class=\"collapse in\" id=\"collapse-comments\">\n",
+            "{comments}",
+            "\t</div>\n",
+            "</div>\n",
+        ),
+        panel = panel,
+        name = escape(&torrent.name),
+        top_code = top_level_code(torrent.category),
+        top_name = torrent.category.top_level_name(),
+        code = torrent.category.code(),
+        category = torrent.category.name(),
+        timestamp = torrent.date.unix(),
+        date = display_date(torrent.date),
+        submitter = submitter,
+        seeders = torrent.seeders,
+        information = information,
+        leechers = torrent.leechers,
+        size = torrent.size,
+        completed = torrent.completed_downloads,
+        info_hash = details.info_hash,
+        id = torrent.id,
+        magnet = magnet,
+        description = escape(&details.description),
+        files = render_files(&details.files, 3),
+        comment_count = details.comments.len(),
+        comments = details
+            .comments
+            .iter()
+            .enumerate()
+            .map(|(i, c)| render_comment(i + 1, c))
+            .collect::<String>(),
+    ));
+    html.push_str(page_foot());
+
+    html
+}
+
+// Items of the file list, indented by depth tabs.
+fn render_files(entries: &[FileEntry], depth: usize) -> String {
+    let tabs = "\t".repeat(depth);
+    let mut html = String::new();
+
+    for entry in entries {
+        match entry {
+            FileEntry::File { name, size } => html.push_str(&format!(
+                "{}<li><i class=\"fa fa-file\"></i>{} <span class=\"file-size\">({})</span></li>\n",
+                tabs,
+                escape(name),
+                size
+            )),
+            FileEntry::Folder { name, entries } => html.push_str(&format!(
+                "{tabs}<li><a href=\"\" class=\"folder\"><i class=\"fa fa-folder-open\"></i>{}</a>\n{tabs}\t<ul data-show=\"yes\">\n{}{tabs}\t</ul>\n{tabs}</li>\n",
+                escape(name),
+                render_files(entries, depth + 2),
+                tabs = tabs
+            )),
+        }
+    }
+
+    html
+}
+
+// Panel of the i-th comment.
+fn render_comment(i: usize, comment: &Comment) -> String {
+    format!(
+        concat!(
+            "\t\t<div class=\"panel panel-default comment-panel\" id=\"com-{i}\">\n",
+            "\t\t\t<div class=\"panel-body\">\n",
+            "\t\t\t\t<div class=\"col-md-2\">\n",
+            "\t\t\t\t\t<p>\n",
+            "\t\t\t\t\t\t<a class=\"text-default\" href=\"/user/{author}\" data-toggle=\"tooltip\" title=\"User\">{author}</a>\n",
+            "\t\t\t\t\t</p>\n",
+            "\t\t\t\t\t<img class=\"avatar\" src=\"/static/img/avatar/default.png\" alt=\"User\">\n",
+            "\t\t\t\t</div>\n",
+            "\t\t\t\t<div class=\"col-md-10 comment\">\n",
+            "\t\t\t\t\t<div class=\"row comment-details\">\n",
+            "\t\t\t\t\t\t<a href=\"#com-{i}\"><small data-timestamp-swap data-timestamp=\"{timestamp}\">{date} UTC</small></a>\n",
+            "\t\t\t\t\t</div>\n",
+            "\t\t\t\t\t<div class=\"row comment-body\">\n",
+            "\t\t\t\t\t\t<div markdown-text class=\"comment-content\" id=\"torrent-comment{i}\">{text}</div>\n",
+            "\t\t\t\t\t</div>\n",
+            "\t\t\t\t</div>\n",
+            "\t\t\t</div>\n",
+            "\t\t</div>\n",
+        ),
+        i = i,
+        author = escape(&comment.author),
+        timestamp = comment.date.unix(),
+        date = display_date(comment.date),
+        text = escape(&comment.text),
+    )
+}
+
+/// Render the RSS feed of a search with the given results. Links in the feed
+/// are absolute, made from `base_url` (e.g. `https://nyaa.si`).
+///
+/// The feed has no magnet links, only info hashes: torrents without a magnet
+/// link get a made up info hash.
+pub fn render_rss(torrents: &[Torrent], base_url: &str) -> String {
+    let mut xml = format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n",
+            "<rss xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:nyaa=\"https://nyaa.si/xmlns/nyaa\" version=\"2.0\">\n",
+            "\t<channel>\n",
+            "\t\t<title>Nyaa - Home - Torrent File RSS</title>\n",
+            "\t\t<description>RSS Feed for Home</description>\n",
+            "\t\t<link>{url}/</link>\n",
+            "\t\t<atom:link href=\"{url}/?page=rss\" rel=\"self\" type=\"application/rss+xml\" />\n",
+        ),
+        url = escape(base_url)
+    );
+
+    for torrent in torrents {
+        let yes_no = |b| if b { "Yes" } else { "No" };
+        let info_hash = info_hash(torrent).to_hex();
+        xml.push_str(&format!(
+            concat!(
+                "\t\t<item>\n",
+                "\t\t\t<title>{name}</title>\n",
+                "\t\t\t\t<link>{url}/download/{id}.torrent</link>\n",
+                "\t\t\t\t<guid isPermaLink=\"true\">{url}/view/{id}</guid>\n",
+                "\t\t\t\t<pubDate>{date}</pubDate>\n",
+                "\t\t\t\t<nyaa:seeders>{seeders}</nyaa:seeders>\n",
+                "\t\t\t\t<nyaa:leechers>{leechers}</nyaa:leechers>\n",
+                "\t\t\t\t<nyaa:downloads>{completed}</nyaa:downloads>\n",
+                "\t\t\t\t<nyaa:infoHash>{info_hash}</nyaa:infoHash>\n",
+                "\t\t\t\t<nyaa:categoryId>{code}</nyaa:categoryId>\n",
+                "\t\t\t\t<nyaa:category>{category}</nyaa:category>\n",
+                "\t\t\t\t<nyaa:size>{size}</nyaa:size>\n",
+                "\t\t\t\t<nyaa:comments>{comments}</nyaa:comments>\n",
+                "\t\t\t\t<nyaa:trusted>{trusted}</nyaa:trusted>\n",
+                "\t\t\t\t<nyaa:remake>{remake}</nyaa:remake>\n",
+                "\t\t\t\t<description><![CDATA[<a href=\"{url}/view/{id}\">#{id} | {name}</a> | {size} | {category} | {upper_hash}]]></description>\n",
+                "\t\t</item>\n",
+            ),
+            url = escape(base_url),
+            id = torrent.id,
+            name = escape(&torrent.name),
+            date = torrent.date.to_rfc2822(),
+            seeders = torrent.seeders,
+            leechers = torrent.leechers,
+            completed = torrent.completed_downloads,
+            info_hash = info_hash,
+            code = torrent.category.code(),
+            category = torrent.category,
+            size = torrent.size,
+            comments = torrent.comments,
+            trusted = yes_no(torrent.status == Status::Trusted),
+            remake = yes_no(torrent.status == Status::Remake),
+            upper_hash = info_hash.to_uppercase(),
+        ));
+    }
+
+    xml.push_str("\t</channel>\n</rss>\n");
+    xml
+}
+
+/// Make up a .torrent file for a torrent: a single file with the torrent's
+/// name and size. The pieces are not the hashes of any real data so the
+/// file's info hash is not the one of the torrent's magnet link.
+pub fn render_torrent_file(torrent: &Torrent) -> Vec<u8> {
+    // Keep the number of pieces reasonable for big torrents.
+    let mut piece_length = 1u64 << 18;
+    while torrent.size.bytes() / piece_length > 1000 {
+        piece_length *= 2;
+    }
+    let count = torrent.size.bytes().div_ceil(piece_length);
+
+    let mut pieces = vec![];
+    for i in 0..count.max(1) {
+        let piece = format!("{}:{}", torrent.id, i);
+        pieces.extend_from_slice(&sha1_smol::Sha1::from(piece).digest().bytes());
+    }
+
+    let mut bytes = vec![];
+    bytes.extend_from_slice(b"d8:announce");
+    bytes.extend_from_slice(format!("{}:{}", TRACKER.len(), TRACKER).as_bytes());
+    bytes.extend_from_slice(b"4:infod6:lengthi");
+    bytes.extend_from_slice(format!("{}e", torrent.size.bytes()).as_bytes());
+    bytes.extend_from_slice(format!("4:name{}:{}", torrent.name.len(), torrent.name).as_bytes());
+    bytes.extend_from_slice(format!("12:piece lengthi{}e", piece_length).as_bytes());
+    bytes.extend_from_slice(format!("6:pieces{}:", pieces.len()).as_bytes());
+    bytes.extend_from_slice(&pieces);
+    bytes.extend_from_slice(b"ee");
+
+    bytes
+}
+
+// Start of a page up to the <div class="container"> with the content.
+fn page_head(title: &str) -> String {
+    format!(
+        concat!(
+            "<!DOCTYPE html>\n",
+            "<html lang=\"en\">\n",
+            "\t<head>\n",
+            "\t\t<meta charset=\"utf-8\">\n",
+            "\t\t<title>{} :: Nyaa</title>\n",
+            "\t</head>\n",
+            "\t<body>\n",
+            "\t\t<!-- Fixed navbar -->\n",
+            "\t\t<nav class=\"navbar navbar-default navbar-static-top navbar-inverse\">\n",
+            "\t\t\t<div class=\"container\">\n",
+            "\t\t\t\t<a class=\"navbar-brand\" href=\"/\">Nyaa</a>\n",
+            "\t\t\t</div>\n",
+            "\t\t</nav>\n",
+            "\t\t<div class=\"container\">\n",
+        ),
+        title
+    )
+}
+
+// End of a page started by page_head.
+fn page_foot() -> &'static str {
+    concat!("\t\t</div>\n", "\t</body>\n", "</html>\n")
+}
+
+// Code of the top-level category of a category (e.g. "1_0" for "1_2").
+fn top_level_code(category: Category) -> String {
+    let code = category.code();
+    let top = code.split('_').next().unwrap_or(code);
+
+    format!("{}_0", top)
+}
+
+// Date the way Nyaa.si shows it (e.g. "2020-09-13 12:26").
+fn display_date(date: Timestamp) -> String {
+    date.to_iso8601()[..16].replace('T', " ")
+}
+
+// Escape text for html and xml.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,190 @@
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "time")]
+extern crate time;
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Struct that represents a point in time with a precision of one second, as
+/// Nyaa.si shows dates.
+///
+/// It is displayed in ISO 8601 (e.g. "2020-09-13T12:26:40Z"). With the
+/// `chrono` or the `time` feature it can be converted to the types of those
+/// crates.
+///
+/// ```
+/// use nyaadesu::Timestamp;
+///
+/// let date = Timestamp::from_unix(1_600_000_000);
+/// assert_eq!(date.to_string(), "2020-09-13T12:26:40Z");
+/// assert_eq!(date.relative_to(Timestamp::from_unix(1_600_010_800)), "3 hours ago");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// Create a timestamp from the number of seconds since 1970-01-01 UTC.
+    pub fn from_unix(seconds: u64) -> Timestamp {
+        Timestamp(seconds)
+    }
+
+    /// The current time.
+    pub fn now() -> Timestamp {
+        Timestamp::from(SystemTime::now())
+    }
+
+    /// Number of seconds since 1970-01-01 UTC.
+    pub fn unix(self) -> u64 {
+        self.0
+    }
+
+    /// Convert to a `SystemTime`. Returns `None` if the timestamp is out of
+    /// the platform's range.
+    pub fn to_system_time(self) -> Option<SystemTime> {
+        UNIX_EPOCH.checked_add(Duration::from_secs(self.0))
+    }
+
+    /// Format as ISO 8601 in UTC (e.g. "2020-09-13T12:26:40Z").
+    pub fn to_iso8601(self) -> String {
+        let days = (self.0 / 86400) as i64;
+        let seconds = self.0 % 86400;
+        let (year, month, day) = civil_from_days(days);
+
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    }
+
+    /// Format as RFC 2822, the way RSS feeds write dates (e.g. "Sun, 13 Sep
+    /// 2020 12:26:40 +0000").
+    pub fn to_rfc2822(self) -> String {
+        const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+
+        let days = (self.0 / 86400) as i64;
+        let seconds = self.0 % 86400;
+        let (year, month, day) = civil_from_days(days);
+
+        format!(
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} +0000",
+            // 1970-01-01 was a Thursday.
+            WEEKDAYS[(days % 7) as usize],
+            day,
+            MONTHS[(month - 1) as usize],
+            year,
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    }
+
+    /// Format relatively to now (e.g. "3 hours ago").
+    pub fn relative(self) -> String {
+        self.relative_to(Timestamp::now())
+    }
+
+    /// Format relatively to another point in time (e.g. "3 hours ago" or "in
+    /// 2 days").
+    pub fn relative_to(self, now: Timestamp) -> String {
+        let (seconds, future) = if now.0 >= self.0 {
+            (now.0 - self.0, false)
+        } else {
+            (self.0 - now.0, true)
+        };
+
+        let (count, unit) = match seconds {
+            0..=59 => return "just now".to_string(),
+            60..=3599 => (seconds / 60, "minute"),
+            3600..=86399 => (seconds / 3600, "hour"),
+            86400..=2_591_999 => (seconds / 86400, "day"),
+            2_592_000..=31_535_999 => (seconds / 2_592_000, "month"),
+            _ => (seconds / 31_536_000, "year"),
+        };
+        let plural = if count == 1 { "" } else { "s" };
+
+        if future {
+            format!("in {} {}{}", count, unit, plural)
+        } else {
+            format!("{} {}{} ago", count, unit, plural)
+        }
+    }
+
+    /// Convert to a `chrono::DateTime<Utc>`. Returns `None` if the timestamp
+    /// is out of chrono's range.
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let seconds = i64::try_from(self.0).ok()?;
+        chrono::DateTime::from_timestamp(seconds, 0)
+    }
+
+    /// Convert to a `time::OffsetDateTime` in UTC. Returns `None` if the
+    /// timestamp is out of time's range.
+    #[cfg(feature = "time")]
+    pub fn to_time(self) -> Option<time::OffsetDateTime> {
+        let seconds = i64::try_from(self.0).ok()?;
+        time::OffsetDateTime::from_unix_timestamp(seconds).ok()
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_iso8601())
+    }
+}
+
+// Times before 1970 are clamped to 1970-01-01.
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Timestamp {
+        Timestamp(
+            time.duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        )
+    }
+}
+
+// Number of days between 1970-01-01 and the given date (see
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil).
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Date of the day that is the given number of days after 1970-01-01 (the
+// inverse of `days_from_civil`).
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
@@ -1,128 +1,199 @@
+extern crate reqwest;
+
+use crate::category::*;
+use crate::magnet::*;
+use crate::size::*;
+use crate::timestamp::*;
+
 /// Struct that represents a torrent and contains some of its basic information.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Torrent {
+    /// Id of the torrent on Nyaa.si (the number in `https://nyaa.si/view/<id>`).
+    pub id: u32,
     /// The category of the torrent.
     pub category: Category,
     /// The name of the torrent.
     pub name: String,
-    /// Links for the torrent (magnet and .torrent) as a pair. If you want to
-    /// get a url to the .torrent file or the magnet please use the
-    /// [`torrent_file`] or the [`magnet_link`] methods.
-    ///
-    /// [`torrent_file`]: #method.torrent_file
-    /// [`magnet_link`]: #method.magnet_link
-    pub links: (Option<String>, Option<String>),
-    /// Total size of the torrent's files in bytes.
-    pub size: u64,
-    /// Date of pubblication of the torrent as unix timestamp.
-    pub date: u64,
+    /// Links to the torrent's .torrent file, magnet and page.
+    pub links: TorrentLinks,
+    /// Total size of the torrent's files.
+    pub size: ByteSize,
+    /// Date of pubblication of the torrent.
+    pub date: Timestamp,
     /// Number of seeders.
     pub seeders: u32,
     /// Number of leechers.
     pub leechers: u32,
     /// Number of completed downloads.
     pub completed_downloads: u32,
+    /// Number of comments.
+    pub comments: u32,
+    /// How Nyaa.si marks the torrent (trusted, remake...).
+    pub status: Status,
 }
 
 impl Torrent {
-    /// Extract `.torrent`'s file url as `String`.
+    /// Extract `.torrent`'s file url as `String`. Relative links (i.e. the
+    /// ones not scraped through a [`NyaaClient`]) are resolved against the
+    /// url of the torrent's site, as told by its category (e.g.
+    /// `https://sukebei.nyaa.si` for Sukebei's categories).
+    ///
+    /// [`NyaaClient`]: ./struct.NyaaClient.html
     pub fn torrent_file(&self) -> Option<String> {
-        let mut result = String::from("https://nyaa.si");
-        let (first, second) = &self.links;
-
-        if let Some(torrent_file) = first {
-            if torrent_file.starts_with("/download") {
-                result.push_str(torrent_file);
-                return Some(result.clone());
-            }
-        }
-
-        if let Some(torrent_file) = second {
-            if torrent_file.starts_with("/download") {
-                result.push_str(torrent_file);
-                return Some(result.clone());
-            }
-        }
-
-        None
+        self.links
+            .download_url
+            .as_deref()
+            .map(|link| self.absolute_url(link))
     }
 
     /// Extract magnet link as `String`.
     pub fn magnet_link(&self) -> Option<String> {
-        let (first, second) = &self.links;
+        self.links.magnet.clone()
+    }
 
-        if let Some(magnet) = first {
-            if magnet.starts_with("magnet") {
-                return Some(magnet.clone());
-            }
+    /// Parse the magnet link into a [`Magnet`]. Returns `None` if the torrent
+    /// has no magnet link or if it can't be parsed.
+    ///
+    /// [`Magnet`]: ./struct.Magnet.html
+    pub fn magnet(&self) -> Option<Magnet> {
+        self.magnet_link()?.parse().ok()
+    }
+
+    /// Url of the torrent's page. Relative links are resolved like in
+    /// [`torrent_file`].
+    ///
+    /// [`torrent_file`]: #method.torrent_file
+    pub fn view_page(&self) -> Option<String> {
+        self.links
+            .view_url
+            .as_deref()
+            .map(|link| self.absolute_url(link))
+    }
+
+    // Resolve a link scraped from the torrent's site against the site's url.
+    fn absolute_url(&self, link: &str) -> String {
+        if link.starts_with('/') {
+            format!("{}{}", self.category.site().base_url(), link)
+        } else {
+            link.to_string()
         }
+    }
 
-        if let Some(magnet) = second {
-            if magnet.starts_with("magnet") {
-                return Some(magnet.clone());
+    // Make the links of the torrent absolute.
+    pub(crate) fn resolve_links(&mut self, base_url: &str) {
+        for link in [&mut self.links.download_url, &mut self.links.view_url]
+            .iter_mut()
+            .filter_map(|l| l.as_mut())
+        {
+            if link.starts_with('/') {
+                link.insert_str(0, base_url);
             }
         }
-
-        None
     }
 }
 
-/// Enum that encodes a torrent's category.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Category {
-    Anime(Anime),
-    Audio(Audio),
-    Literature(Literature),
-    LiveAction(LiveAction),
-    Pictures(Pictures),
-    Software(Software),
+/// Enum that encodes how Nyaa.si marks a torrent: in listings trusted uploads
+/// are green and remakes are red. There is no batch status: Nyaa.si's listing
+/// has no batch marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Status {
+    /// Nothing special.
+    Normal,
+    /// Uploaded by a trusted user.
+    Trusted,
+    /// Marked as a remake (e.g. a re-encode of someone else's release).
+    Remake,
 }
 
-/// Enum that encodes variants of anime torrents.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Anime {
-    AnimeMusicVideo,
-    EnglishTranslated,
-    NonEnglishTranslated,
-    Raw,
+impl Status {
+    // Status of a torrent from its trusted and remake flags. Nyaa.si shows
+    // torrents that are both as remakes.
+    pub(crate) fn from_flags(trusted: bool, remake: bool) -> Status {
+        if remake {
+            Status::Remake
+        } else if trusted {
+            Status::Trusted
+        } else {
+            Status::Normal
+        }
+    }
 }
 
-/// Enum that encodes variants of audio torrents.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Audio {
-    Lossless,
-    Lossy,
+/// Struct that holds the links of a torrent as they were found on Nyaa.si.
+/// Links to Nyaa.si itself may be relative (e.g. `/download/644786.torrent`)
+/// if they were not scraped through a [`NyaaClient`].
+///
+/// [`NyaaClient`]: ./struct.NyaaClient.html
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TorrentLinks {
+    /// Url of the .torrent file.
+    pub download_url: Option<String>,
+    /// Magnet link.
+    pub magnet: Option<String>,
+    /// Url of the torrent's page.
+    pub view_url: Option<String>,
 }
 
-/// Enum that encodes variants of literature torrents.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Literature {
-    EnglishTranslated,
-    NonEnglishTranslated,
-    Raw,
+// Get the id of a torrent from a link to its page (e.g. "/view/644786" or
+// "https://nyaa.si/view/644786#comments").
+pub(crate) fn view_id(link: &str) -> Option<u32> {
+    let start = link.find("/view/")? + "/view/".len();
+    let id = link[start..].split(|c: char| !c.is_ascii_digit()).next()?;
+
+    id.parse().ok()
 }
 
-/// Enum that encodes variants of live action torrents.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum LiveAction {
-    EnglishTranslated,
-    IdolPromotionalVideo,
-    NonEnglishTranslated,
-    Raw,
+/// Struct that represents everything Nyaa.si shows about a torrent on its page
+/// (e.g. <https://nyaa.si/view/644786>).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TorrentDetails {
+    /// Basic information about the torrent: the same found in search results.
+    pub torrent: Torrent,
+    /// Name of the user that submitted the torrent, `None` if it was submitted
+    /// anonymously.
+    pub submitter: Option<String>,
+    /// Information url set by the submitter (usually the website of the group
+    /// that released the torrent).
+    pub information: Option<String>,
+    /// Info hash of the torrent.
+    pub info_hash: InfoHash,
+    /// Description of the torrent, in markdown.
+    pub description: String,
+    /// Files and folders contained in the torrent.
+    pub files: Vec<FileEntry>,
+    /// Comments left on the torrent's page, oldest first.
+    pub comments: Vec<Comment>,
 }
 
-/// Enum that encodes variants of pictures torrents.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Pictures {
-    Graphics,
-    Photos,
+/// Enum that represents an entry of a torrent's file tree.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "lowercase"))]
+pub enum FileEntry {
+    /// A file and its size in bytes.
+    File { name: String, size: ByteSize },
+    /// A folder and its content.
+    Folder {
+        name: String,
+        entries: Vec<FileEntry>,
+    },
 }
 
-/// Enum that encodes variants of software torrents.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Software {
-    Applications,
-    Games,
+/// Struct that represents a comment on a torrent's page.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comment {
+    /// Name of the user that wrote the comment.
+    pub author: String,
+    /// Date of pubblication of the comment.
+    pub date: Timestamp,
+    /// Text of the comment, in markdown.
+    pub text: String,
 }
 
 /// Emun that encodes possible errors
@@ -130,7 +201,18 @@ pub enum Software {
 pub enum Error {
     /// Error with the request e.g. the server do not respond or you don't have
     /// access to it.
-    Request,
+    Request(reqwest::Error),
+    /// The request took too long.
+    Timeout(reqwest::Error),
+    /// The server answered with an error status (e.g. 404 if a torrent does
+    /// not exist, 429 if you are making too many requests or 503 if Nyaa.si
+    /// is down).
+    Status(u16),
+    /// Error while reading a page.
+    Io(std::io::Error),
+    /// The client's configuration is not valid (e.g. the user agent contains
+    /// characters that can't be sent in a request).
+    Configuration(String),
     /// Scraping error. Since all the data is obtained via scraping of html
     /// pages if somehting in how Nyaa.si's pages are generated is changed
     /// searching for torrents may fail. This should hopefully never happen but
@@ -139,5 +221,128 @@ pub enum Error {
     /// be updated.
     ///
     /// [GitHub]: https://github.com/grastello/nyaadesu
-    Scraping,
+    Scraping(ScrapingError),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Request(e) => write!(f, "request failed: {}", e),
+            Error::Timeout(_) => write!(f, "request timed out"),
+            Error::Status(status) => write!(f, "server answered with status {}", status),
+            Error::Io(e) => write!(f, "could not read page: {}", e),
+            Error::Configuration(e) => write!(f, "invalid configuration: {}", e),
+            Error::Scraping(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Request(e) | Error::Timeout(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Scraping(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Error {
+        if e.is_timeout() {
+            Error::Timeout(e)
+        } else if let Some(status) = e.status() {
+            Error::Status(status.as_u16())
+        } else {
+            Error::Request(e)
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<ScrapingError> for Error {
+    fn from(e: ScrapingError) -> Error {
+        Error::Scraping(e)
+    }
+}
+
+/// Struct that tells where scraping failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ScrapingError {
+    /// Kind of page that was being scraped.
+    pub page: PageKind,
+    /// Index of the row (or of the RSS item) that could not be scraped, if the
+    /// error is about a single row.
+    pub row: Option<usize>,
+    /// Name of the field that could not be extracted (e.g. "size" or "date").
+    pub field: &'static str,
+    /// Value that was found but not understood (e.g. "1.4 XiB"), if the field
+    /// was there at all.
+    pub value: Option<String>,
+}
+
+impl ScrapingError {
+    pub(crate) fn new(page: PageKind, row: Option<usize>, field: &'static str) -> ScrapingError {
+        ScrapingError {
+            page,
+            row,
+            field,
+            value: None,
+        }
+    }
+
+    // Attach the value that could not be parsed, if there was one.
+    pub(crate) fn with_value(mut self, value: Option<String>) -> ScrapingError {
+        self.value = value;
+        self
+    }
+}
+
+impl std::fmt::Display for ScrapingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "could not scrape {}", self.field)?;
+        if let Some(row) = self.row {
+            write!(f, " of row {}", row)?;
+        }
+        write!(f, " of {}", self.page)?;
+        if let Some(value) = &self.value {
+            write!(f, " (found {:?})", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ScrapingError {}
+
+/// Enum that encodes the kinds of pages that are scraped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PageKind {
+    /// A page of search results.
+    Search,
+    /// A torrent's page.
+    View,
+    /// The RSS feed.
+    Rss,
+    /// A .torrent file.
+    TorrentFile,
+}
+
+impl std::fmt::Display for PageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PageKind::Search => write!(f, "a search page"),
+            PageKind::View => write!(f, "a torrent page"),
+            PageKind::Rss => write!(f, "the RSS feed"),
+            PageKind::TorrentFile => write!(f, "a .torrent file"),
+        }
+    }
 }
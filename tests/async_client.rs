@@ -0,0 +1,107 @@
+extern crate futures_util;
+extern crate nyaadesu;
+extern crate tokio;
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{StreamExt, TryStreamExt};
+use nyaadesu::testing::{self, Response};
+use nyaadesu::*;
+
+fn client(server: &common::Server) -> AsyncNyaaClient {
+    NyaaClientBuilder::new()
+        .base_url(&server.url)
+        .build_async()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn search_stops_at_the_last_page() {
+    let server = common::serve(common::nyaa(2));
+
+    let torrents = client(&server).torrent_search("madoka").await.unwrap();
+    assert_eq!(torrents.len(), 4);
+    assert_eq!(server.requests(), 2);
+}
+
+#[tokio::test]
+async fn search_results_are_lazy() {
+    let server = common::serve(common::nyaa(10));
+    let client = client(&server);
+
+    let query = SearchQuery::new("madoka").limit(3);
+    let torrents: Vec<_> = client.search_results(&query).try_collect().await.unwrap();
+    assert_eq!(torrents.len(), 3);
+    assert_eq!(server.requests(), 2);
+
+    let query = SearchQuery::new("madoka").max_pages(3);
+    assert_eq!(client.search_pages(&query).count().await, 3);
+    assert_eq!(server.requests(), 5);
+}
+
+#[tokio::test]
+async fn details_and_rss() {
+    let server = common::serve(common::nyaa(1));
+    let client = client(&server);
+
+    assert_eq!(
+        client.torrent_details(1300001).await.unwrap().torrent.id,
+        1300001
+    );
+    assert_eq!(
+        client
+            .search_rss(&SearchQuery::new(""))
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let file = client.download_torrent(1300001).await.unwrap();
+    assert_eq!(file, common::TORRENT_FILE);
+
+    match client.torrent_details(1).await {
+        Err(Error::Status(404)) => (),
+        other => panic!("expected a 404, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn self_check_reads_a_search_and_a_view_page() {
+    let server =
+        testing::FakeNyaa::new(vec![testing::torrent(1, "Little Witch Academia - 01")]).start();
+
+    let check = client(&server).self_check().await.unwrap();
+    assert!(check.is_ok(), "{}", check);
+    assert!(check.field(PageKind::View, "files").is_some());
+    assert_eq!(server.requests(), 2);
+}
+
+#[tokio::test]
+async fn throttled_requests_are_tried_again() {
+    let route = common::nyaa(1);
+    let tries = AtomicUsize::new(0);
+    let server = testing::serve_responses(move |path| {
+        if tries.fetch_add(1, Ordering::SeqCst) < 2 {
+            Response::status(429).header("Retry-After", "0")
+        } else {
+            Response::from(route(path))
+        }
+    });
+    let client = NyaaClientBuilder::new()
+        .base_url(&server.url)
+        .retry(RetryPolicy::new())
+        .rate_limit(1, Duration::from_millis(50))
+        .build_async()
+        .unwrap();
+
+    let start = Instant::now();
+    assert_eq!(client.torrent_search("madoka").await.unwrap().len(), 2);
+    assert_eq!(server.requests(), 3);
+    // Only a lower bound with some slack: the bucket starts filling when the
+    // client is built.
+    assert!(start.elapsed() >= Duration::from_millis(80));
+}
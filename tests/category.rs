@@ -0,0 +1,106 @@
+extern crate nyaadesu;
+
+use nyaadesu::*;
+
+#[test]
+fn categories_round_trip() {
+    assert_eq!(Site::Nyaa.categories().count(), 17);
+    assert_eq!(Site::Sukebei.categories().count(), 7);
+    assert_eq!(Category::all().count(), 24);
+
+    for category in Site::Nyaa.categories() {
+        assert_eq!(Category::from_code(category.code()), Some(category));
+        assert_eq!(category.code().parse(), Ok(category));
+    }
+
+    for category in Category::all() {
+        assert_eq!(
+            Category::from_site_code(category.site(), category.code()),
+            Some(category)
+        );
+        assert_eq!(category.to_string().parse(), Ok(category));
+        assert_eq!(category.to_string().to_lowercase().parse(), Ok(category));
+    }
+
+    for anime in Anime::all() {
+        assert_eq!(Anime::from_code(anime.code()), Some(anime));
+        assert_eq!(anime.to_string().parse(), Ok(anime));
+    }
+    assert_eq!(Anime::from_code("2_1"), None);
+    assert_eq!(
+        "Idol/Promotional Video".parse(),
+        Ok(LiveAction::IdolPromotionalVideo)
+    );
+}
+
+#[test]
+fn category_names() {
+    let category = Category::LiveAction(LiveAction::IdolPromotionalVideo);
+
+    assert_eq!(category.code(), "4_2");
+    assert_eq!(category.top_level_name(), "Live Action");
+    assert_eq!(category.name(), "Idol/Promotional Video");
+    assert_eq!(category.to_string(), "Live Action - Idol/Promotional Video");
+    assert_eq!(
+        "7_1".parse::<Category>(),
+        Err(UnknownCategory("7_1".to_string()))
+    );
+}
+
+#[test]
+fn category_filters() {
+    assert_eq!("0_0".parse(), Ok(CategoryFilter::All));
+    assert_eq!("all".parse(), Ok(CategoryFilter::All));
+    assert_eq!("1_0".parse(), Ok(CategoryFilter::Anime));
+    assert_eq!("live action".parse(), Ok(CategoryFilter::LiveAction));
+    assert_eq!(
+        "Anime - Raw".parse(),
+        Ok(CategoryFilter::Only(Category::Anime(Anime::Raw)))
+    );
+    assert!("1_9".parse::<CategoryFilter>().is_err());
+
+    assert_eq!(CategoryFilter::Pictures.code(), "5_0");
+    assert_eq!(CategoryFilter::Pictures.to_string(), "Pictures");
+    assert_eq!(
+        CategoryFilter::from(Category::Audio(Audio::Lossy)).to_string(),
+        "Audio - Lossy"
+    );
+
+    let raw = Category::Anime(Anime::Raw);
+    assert!(CategoryFilter::All.contains(raw));
+    assert!(CategoryFilter::Anime.contains(raw));
+    assert!(!CategoryFilter::Audio.contains(raw));
+    assert!(CategoryFilter::Only(raw).contains(raw));
+    assert!(!CategoryFilter::Only(Category::Anime(Anime::EnglishTranslated)).contains(raw));
+}
+
+#[test]
+fn sukebei_categories() {
+    let manga = Category::Art(Art::Manga);
+
+    assert_eq!(manga.code(), "1_4");
+    assert_eq!(manga.site(), Site::Sukebei);
+    assert_eq!(manga.to_string(), "Art - Manga");
+    assert_eq!(
+        Category::from_code("1_4"),
+        Some(Category::Anime(Anime::Raw))
+    );
+    assert_eq!(Category::from_site_code(Site::Sukebei, "1_4"), Some(manga));
+    assert_eq!(
+        "Real Life - Photobooks and Pictures".parse(),
+        Ok(Category::RealLife(RealLife::PhotobooksAndPictures))
+    );
+
+    assert_eq!(
+        CategoryFilter::from_site_code(Site::Sukebei, "1_0"),
+        Some(CategoryFilter::Art)
+    );
+    assert_eq!(CategoryFilter::RealLife.code(), "2_0");
+    assert!(CategoryFilter::Art.contains(manga));
+    assert!(!CategoryFilter::Anime.contains(manga));
+
+    assert_eq!(CategoryFilter::Art.site(), Some(Site::Sukebei));
+    assert_eq!(CategoryFilter::Only(manga).site(), Some(Site::Sukebei));
+    assert_eq!(CategoryFilter::Anime.site(), Some(Site::Nyaa));
+    assert_eq!(CategoryFilter::All.site(), None);
+}
@@ -0,0 +1,86 @@
+extern crate nyaadesu;
+
+use nyaadesu::*;
+
+const SEARCH_PAGE: &str = include_str!("fixtures/search.html");
+const VIEW_PAGE: &str = include_str!("fixtures/view.html");
+
+#[test]
+fn current_pages_pass() {
+    let search = check_search_page(SEARCH_PAGE);
+    assert!(search.is_ok(), "{}", search);
+    let size = search.field(PageKind::Search, "size").unwrap();
+    assert_eq!((size.checked, size.missing), (2, 0));
+
+    let view = check_view_page(VIEW_PAGE);
+    assert!(view.is_ok(), "{}", view);
+    assert_eq!(view.field(PageKind::View, "comment").unwrap().checked, 1);
+
+    let synthetic = check_search_page(include_str!("fixtures/synthetic/last_page.html"));
+    assert!(synthetic.is_ok(), "{}", synthetic);
+}
+
+#[test]
+fn unknown_values_are_reported() {
+    let page = SEARCH_PAGE
+        .replace("title=\"Anime - Raw\"", "title=\"Anime - Audio Drama\"")
+        .replace("1.4 GiB", "1.4 XiB")
+        .replace("350.2 MiB", "350.2 XiB");
+    let check = check_search_page(&page);
+
+    assert!(!check.is_ok());
+    let category = check.field(PageKind::Search, "category").unwrap();
+    assert_eq!(category.unknown, vec!["Anime - Audio Drama"]);
+    let size = check.field(PageKind::Search, "size").unwrap();
+    assert_eq!(size.unknown, vec!["1.4 XiB", "350.2 XiB"]);
+    assert_eq!(size.missing, 0);
+    assert!(check.field(PageKind::Search, "date").unwrap().is_ok());
+    assert_eq!(
+        check.failures().map(|f| f.field).collect::<Vec<_>>(),
+        vec!["category", "size"]
+    );
+    assert!(check
+        .to_string()
+        .contains("size of a search page: 2 unknown values (\"1.4 XiB\", \"350.2 XiB\")"));
+
+    let view = VIEW_PAGE
+        .replace(
+            "<a href=\"/?c=1_0\">Anime</a> - <a href=\"/?c=1_2\">English-translated</a>",
+            "<a href=\"/?c=1_0\">Anime</a> - <a href=\"/?c=1_9\">Audio Drama</a>",
+        )
+        .replace(
+            "<div class=\"col-md-5\">1.4 GiB</div>",
+            "<div class=\"col-md-5\">1.4 XiB</div>",
+        );
+    let check = check_view_page(&view);
+    assert_eq!(
+        check.field(PageKind::View, "category").unwrap().unknown,
+        vec!["Anime - Audio Drama"]
+    );
+    assert_eq!(
+        check.field(PageKind::View, "size").unwrap().unknown,
+        vec!["1.4 XiB"]
+    );
+}
+
+#[test]
+fn missing_fields_are_counted() {
+    let check = check_search_page(include_str!("fixtures/synthetic/missing_magnet.html"));
+    let magnet = check.field(PageKind::Search, "magnet").unwrap();
+    assert_eq!((magnet.checked, magnet.missing), (2, 1));
+
+    let page = SEARCH_PAGE.replace(" data-timestamp=\"1600000000\"", "");
+    let check = check_search_page(&page);
+    assert_eq!(check.field(PageKind::Search, "date").unwrap().missing, 1);
+
+    let check = check_search_page(include_str!("fixtures/synthetic/empty_search.html"));
+    assert_eq!(
+        check.failures().map(|f| f.field).collect::<Vec<_>>(),
+        vec!["table", "meta"]
+    );
+
+    let check = check_view_page("<html><body>Under maintenance</body></html>");
+    assert_eq!(check.fields.len(), 1);
+    assert_eq!(check.fields[0].field, "panel");
+    assert_eq!(check.fields[0].missing, 1);
+}
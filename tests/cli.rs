@@ -0,0 +1,173 @@
+extern crate nyaadesu;
+extern crate serde_json;
+
+mod common;
+
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+
+use nyaadesu::*;
+
+fn nyaadesu(server: &common::Server, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_nyaadesu"))
+        .arg("--base-url")
+        .arg(&server.url)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn search_formats() {
+    let server = common::serve(common::nyaa(1));
+
+    let table = stdout(&nyaadesu(&server, &["search", "little", "witch"]));
+    let lines = table.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("SIZE"));
+    assert!(lines[1].contains("1.4 GiB"));
+    assert!(lines[1].ends_with("[SubsPlease] Little Witch Academia - 01 (1080p)"));
+
+    let jsonl = stdout(&nyaadesu(
+        &server,
+        &["search", "--format", "jsonl", "witch"],
+    ));
+    let torrents = jsonl
+        .lines()
+        .map(|l| serde_json::from_str::<Torrent>(l).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(torrents.len(), 2);
+    assert_eq!(torrents[0].id, 1300001);
+
+    let csv = stdout(&nyaadesu(&server, &["search", "--format=csv", "witch"]));
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("id,category,name,size,date,"));
+    assert!(lines[1].starts_with("1300001,1_2,"));
+}
+
+#[test]
+fn search_options_reach_the_query() {
+    let server = common::serve(common::nyaa(1));
+
+    let output = nyaadesu(
+        &server,
+        &[
+            "search",
+            "-c",
+            "Anime",
+            "-f",
+            "trusted-only",
+            "-s",
+            "seeders",
+            "-o",
+            "asc",
+            "-n",
+            "1",
+            "witch",
+        ],
+    );
+    assert_eq!(stdout(&output).lines().count(), 2);
+    // The limit is reached on the first page.
+    assert_eq!(server.requests(), 1);
+}
+
+#[test]
+fn exclusions_and_site_categories() {
+    let paths = Arc::new(Mutex::new(vec![]));
+    let route = common::nyaa(1);
+    let seen = paths.clone();
+    let server = common::serve(move |path| {
+        seen.lock().unwrap().push(path.to_string());
+        route(path)
+    });
+    let last_path = || paths.lock().unwrap().last().cloned().unwrap();
+
+    // Terms that start with "-" leave out results on Nyaa.si.
+    stdout(&nyaadesu(&server, &["search", "little", "witch", "-raw"]));
+    assert!(
+        last_path().contains("q=little+witch+-raw"),
+        "{}",
+        last_path()
+    );
+    stdout(&nyaadesu(&server, &["search", "--", "-raw", "witch"]));
+    assert!(last_path().contains("q=-raw+witch"), "{}", last_path());
+
+    // Categories are looked up on the chosen site.
+    let output = nyaadesu(
+        &server,
+        &["--site", "sukebei", "search", "-c", "art", "witch"],
+    );
+    stdout(&output);
+    assert!(last_path().contains("c=1_0"), "{}", last_path());
+    for category in ["anime", "Anime - Raw"].iter() {
+        let output = nyaadesu(
+            &server,
+            &["--site", "sukebei", "search", "-c", category, "witch"],
+        );
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("sukebei has no category"));
+    }
+    let output = nyaadesu(&server, &["search", "-c", "Art - Manga", "witch"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(paths.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn view_magnet_and_download() {
+    let server = common::serve(common::nyaa(1));
+
+    let view = stdout(&nyaadesu(&server, &["view", "1300001"]));
+    assert!(view.contains("Category:    Anime - English-translated"));
+    assert!(view.contains("Files:"));
+
+    let magnet = stdout(&nyaadesu(&server, &["magnet", "1300001"]));
+    assert!(magnet.starts_with("magnet:?xt=urn:btih:"));
+    assert_eq!(magnet.lines().count(), 1);
+
+    let torrent = nyaadesu(&server, &["download", "-O", "-", "1300001"]);
+    assert!(torrent.status.success());
+    assert_eq!(torrent.stdout, common::TORRENT_FILE);
+
+    let path = std::env::temp_dir().join(format!("nyaadesu-cli-{}.torrent", std::process::id()));
+    let output = nyaadesu(
+        &server,
+        &["download", "1300001", "--output", path.to_str().unwrap()],
+    );
+    stdout(&output);
+    assert_eq!(std::fs::read(&path).unwrap(), common::TORRENT_FILE);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn errors_exit_with_a_status() {
+    let server = common::serve(common::nyaa(1));
+
+    let not_found = nyaadesu(&server, &["view", "1"]);
+    assert_eq!(not_found.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&not_found.stderr).contains("404"));
+
+    for args in [
+        &["frobnicate"][..],
+        &["view"],
+        &["view", "abc"],
+        &["search", "--sort", "name", "witch"],
+        &["magnet", "--format", "csv", "1300001"],
+    ]
+    .iter()
+    {
+        let usage = nyaadesu(&server, args);
+        assert_eq!(usage.status.code(), Some(2), "{:?}", args);
+        assert!(String::from_utf8_lossy(&usage.stderr).contains("Usage:"));
+    }
+    assert_eq!(server.requests(), 1);
+}
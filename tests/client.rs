@@ -0,0 +1,362 @@
+extern crate nyaadesu;
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use nyaadesu::testing::{self, FakeNyaa, Response};
+use nyaadesu::*;
+
+fn client(server: &common::Server) -> NyaaClient {
+    NyaaClient::builder().base_url(&server.url).build().unwrap()
+}
+
+#[test]
+fn search_stops_at_the_last_page() {
+    let server = common::serve(common::nyaa(2));
+
+    let torrents = client(&server).torrent_search("madoka").unwrap();
+    assert_eq!(torrents.len(), 4);
+    assert_eq!(server.requests(), 2);
+
+    // Links are made absolute.
+    let torrent_file = torrents[0].torrent_file().unwrap();
+    assert!(torrent_file.starts_with(&server.url), "{}", torrent_file);
+}
+
+#[test]
+fn search_without_page_count_fetches_pages_until_the_empty_one() {
+    // Pages that don't tell how many pages there are.
+    let page = common::search_page(1, 1);
+    let start = page.find("<div class=\"pagination-page-info\">").unwrap();
+    let end = page.rfind("</nav>").unwrap();
+    let page = format!("{}{}", &page[..start], &page[end..]);
+
+    let server = common::serve(move |path: &str| {
+        if path.contains("p=1") {
+            Some(page.clone().into_bytes())
+        } else {
+            Some(common::empty_search_page().into_bytes())
+        }
+    });
+
+    let torrents = client(&server).torrent_search("madoka").unwrap();
+    assert_eq!(torrents.len(), 2);
+    assert_eq!(server.requests(), 2);
+}
+
+#[test]
+fn search_results_are_lazy() {
+    let server = common::serve(common::nyaa(10));
+    let client = client(&server);
+
+    let query = SearchQuery::new("madoka");
+    assert_eq!(client.search_results(&query).take(3).count(), 3);
+    assert_eq!(server.requests(), 2);
+
+    let query = SearchQuery::new("madoka").max_pages(3);
+    assert_eq!(client.search(&query).unwrap().len(), 6);
+    assert_eq!(server.requests(), 5);
+}
+
+#[test]
+fn details_and_rss() {
+    let server = common::serve(common::nyaa(1));
+    let client = client(&server);
+
+    assert_eq!(client.torrent_details(1300001).unwrap().torrent.id, 1300001);
+    assert_eq!(client.search_rss(&SearchQuery::new("")).unwrap().len(), 1);
+
+    let metainfo = client.torrent_metainfo(1300001).unwrap();
+    assert_eq!(
+        metainfo.info_hash.to_hex(),
+        "3ca2c86efa00c4f4620d26ab07f09de8e7f538f6"
+    );
+
+    match client.torrent_details(1) {
+        Err(Error::Status(404)) => (),
+        other => panic!("expected a 404, got {:?}", other),
+    }
+}
+
+#[test]
+fn timeouts_keep_their_cause() {
+    let server = testing::serve_responses(|_: &str| {
+        std::thread::sleep(Duration::from_millis(500));
+        Response::status(200)
+    });
+    let client = NyaaClient::builder()
+        .base_url(&server.url)
+        .timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    match client.torrent_details(1) {
+        Err(e @ Error::Timeout(_)) => {
+            assert!(std::error::Error::source(&e).is_some());
+            assert_eq!(e.to_string(), "request timed out");
+        }
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[test]
+fn fake_nyaa_searches() {
+    let mut torrents = (1..=5)
+        .map(|id| testing::torrent(id, &format!("Little Witch Academia - {:02}", id)))
+        .collect::<Vec<_>>();
+    torrents[1].status = Status::Remake;
+    torrents[2].status = Status::Trusted;
+    torrents[3].category = Category::Anime(Anime::Raw);
+    torrents.push(testing::torrent(6, "Madoka Magica"));
+    let server = FakeNyaa::new(torrents).page_size(2).start();
+    let client = client(&server);
+
+    let ids = |query: &SearchQuery| {
+        client
+            .search(query)
+            .unwrap()
+            .iter()
+            .map(|t| t.id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&SearchQuery::new("witch")), vec![5, 4, 3, 2, 1]);
+    assert_eq!(server.requests(), 3);
+    assert_eq!(ids(&SearchQuery::new("little WITCH 04")), vec![4]);
+    assert_eq!(
+        ids(&SearchQuery::new("").order(Order::Ascending)),
+        vec![1, 2, 3, 4, 5, 6]
+    );
+    assert_eq!(
+        ids(&SearchQuery::new("witch").filter(Filter::NoRemakes)),
+        vec![5, 4, 3, 1]
+    );
+    assert_eq!(
+        ids(&SearchQuery::new("witch").filter(Filter::TrustedOnly)),
+        vec![3]
+    );
+    assert_eq!(
+        ids(&SearchQuery::new("witch").category(Category::Anime(Anime::Raw))),
+        vec![4]
+    );
+    assert_eq!(
+        ids(&SearchQuery::new("").sort(Sort::Seeders)),
+        vec![6, 5, 4, 3, 2, 1]
+    );
+    assert!(ids(&SearchQuery::new("frieren")).is_empty());
+
+    let page = client.search_page(&SearchQuery::new("witch"), 2).unwrap();
+    assert_eq!(page.meta.unwrap().last_page, 3);
+    assert_eq!(
+        page.torrents[0].view_page().unwrap(),
+        format!("{}/view/3", server.url)
+    );
+    assert_eq!(
+        client.search_rss(&SearchQuery::new("witch")).unwrap().len(),
+        2
+    );
+}
+
+#[test]
+fn fake_nyaa_views_and_downloads() {
+    let torrent = testing::torrent(1300001, "Little Witch Academia - 01");
+    let mut details = parse_view_page(common::VIEW_PAGE).unwrap();
+    details.submitter = Some("akko".to_string());
+    let server = FakeNyaa::new(vec![torrent.clone(), testing::torrent(2, "Madoka")])
+        .details(details.clone())
+        .torrent_file(2, common::TORRENT_FILE.to_vec())
+        .start();
+    let client = client(&server);
+
+    let view = client.torrent_details(1300001).unwrap();
+    assert_eq!(view.submitter.as_deref(), Some("akko"));
+    assert_eq!(view.files, details.files);
+    assert_eq!(view.comments, details.comments);
+    assert_eq!(client.torrent_details(2).unwrap().submitter, None);
+
+    let metainfo = client.torrent_metainfo(1300001).unwrap();
+    assert_eq!(metainfo.name, torrent.name);
+    assert_eq!(client.download_torrent(2).unwrap(), common::TORRENT_FILE);
+
+    match client.torrent_details(3) {
+        Err(Error::Status(404)) => (),
+        other => panic!("expected a 404, got {:?}", other),
+    }
+}
+
+#[test]
+fn self_check_reads_a_search_and_a_view_page() {
+    let server = FakeNyaa::new(vec![
+        testing::torrent(1, "Little Witch Academia - 01"),
+        testing::torrent(2, "Little Witch Academia - 02"),
+    ])
+    .start();
+
+    let check = client(&server).self_check().unwrap();
+    assert!(check.is_ok(), "{}", check);
+    assert_eq!(server.requests(), 2);
+    assert_eq!(check.field(PageKind::Search, "size").unwrap().checked, 2);
+    assert!(check.field(PageKind::View, "info hash").is_some());
+
+    let server = common::serve(|path: &str| match path {
+        "/" => Some(common::empty_search_page().into_bytes()),
+        _ => None,
+    });
+    let check = client(&server).self_check().unwrap();
+    assert!(!check.is_ok());
+    assert_eq!(server.requests(), 1);
+    assert!(check.fields.iter().all(|f| f.page == PageKind::Search));
+}
+
+// A server that fails the first requests with the given answer.
+fn flaky(failures: usize, failure: Response) -> common::Server {
+    let route = common::nyaa(1);
+    let tries = AtomicUsize::new(0);
+
+    testing::serve_responses(move |path| {
+        if tries.fetch_add(1, Ordering::SeqCst) < failures {
+            failure.clone()
+        } else {
+            Response::from(route(path))
+        }
+    })
+}
+
+fn retrying_client(server: &common::Server, policy: RetryPolicy) -> NyaaClient {
+    NyaaClient::builder()
+        .base_url(&server.url)
+        .retry(policy.initial_delay(Duration::from_millis(10)))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn throttled_requests_are_tried_again() {
+    let server = flaky(2, Response::status(429).header("Retry-After", "0"));
+    let client = retrying_client(&server, RetryPolicy::new());
+    assert_eq!(client.torrent_search("madoka").unwrap().len(), 2);
+    assert_eq!(server.requests(), 3);
+
+    // Dates in the past mean now.
+    let server = flaky(
+        1,
+        Response::status(503).header("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT"),
+    );
+    let client = retrying_client(&server, RetryPolicy::new());
+    assert_eq!(client.torrent_details(1300001).unwrap().torrent.id, 1300001);
+    assert_eq!(server.requests(), 2);
+
+    // Without Retry-After the delays double: 10ms, 20ms, 40ms.
+    let server = flaky(3, Response::status(502));
+    let client = retrying_client(&server, RetryPolicy::new().jitter(false));
+    let start = Instant::now();
+    assert_eq!(
+        client.download_torrent(1300001).unwrap(),
+        common::TORRENT_FILE
+    );
+    // Sleeps never end early but may end late on a busy machine, so only the
+    // shortest possible wait is checked.
+    assert!(start.elapsed() >= Duration::from_millis(60));
+}
+
+#[test]
+fn retries_give_up() {
+    // Clients don't retry unless asked to.
+    let server = flaky(1, Response::status(503));
+    assert!(client(&server).torrent_search("madoka").is_err());
+    assert_eq!(server.requests(), 1);
+
+    let server = flaky(10, Response::status(503));
+    let client = retrying_client(&server, RetryPolicy::new().max_retries(2));
+    match client.torrent_search("madoka") {
+        Err(Error::Status(503)) => (),
+        other => panic!("expected a 503, got {:?}", other),
+    }
+    assert_eq!(server.requests(), 3);
+
+    // Waiting longer than the maximum delay is not worth it.
+    let server = flaky(1, Response::status(429).header("Retry-After", "3600"));
+    let client = retrying_client(&server, RetryPolicy::new());
+    match client.torrent_search("madoka") {
+        Err(Error::Status(429)) => (),
+        other => panic!("expected a 429, got {:?}", other),
+    }
+    assert_eq!(server.requests(), 1);
+
+    // Missing torrents don't come back.
+    let server = common::serve(common::nyaa(1));
+    let client = retrying_client(&server, RetryPolicy::new());
+    assert!(client.torrent_details(1).is_err());
+    assert_eq!(server.requests(), 1);
+}
+
+#[test]
+fn rate_limit_is_shared_by_clones() {
+    let server = common::serve(common::nyaa(1));
+    let client = NyaaClient::builder()
+        .base_url(&server.url)
+        .rate_limit(2, Duration::from_millis(200))
+        .build()
+        .unwrap();
+    let clone = client.clone();
+
+    // Two requests go out at once, the next three wait 100ms each. Only the
+    // shortest possible wait is checked (with some slack for the time between
+    // building the client and starting the clock), as a busy machine can
+    // make requests slower but never faster.
+    let start = Instant::now();
+    client.torrent_details(1300001).unwrap();
+    clone.torrent_details(1300001).unwrap();
+    client.torrent_search("madoka").unwrap();
+    clone.download_torrent(1300001).unwrap();
+    client.search_rss(&SearchQuery::new("")).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(250));
+    assert_eq!(server.requests(), 5);
+}
+
+#[test]
+fn rate_limit_over_a_very_long_time() {
+    let server = common::serve(common::nyaa(1));
+    let client = NyaaClient::builder()
+        .base_url(&server.url)
+        .rate_limit(1, Duration::MAX)
+        .build()
+        .unwrap();
+
+    // The first request is let through and the next one waits (for ever)
+    // instead of overflowing the delay.
+    client.torrent_details(1300001).unwrap();
+    let waiting = std::thread::spawn(move || client.torrent_details(1300001));
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!waiting.is_finished());
+    assert_eq!(server.requests(), 1);
+}
+
+#[test]
+fn site_selects_the_base_url() {
+    let client = NyaaClient::builder().site(Site::Sukebei).build().unwrap();
+    assert_eq!(client.base_url(), "https://sukebei.nyaa.si");
+    assert_eq!(NyaaClient::new().base_url(), "https://nyaa.si");
+    assert_eq!(client.site(), Site::Sukebei);
+    assert_eq!(NyaaClient::new().site(), Site::Nyaa);
+
+    let guessed = NyaaClient::builder()
+        .base_url("https://sukebei.example.org")
+        .build()
+        .unwrap();
+    assert_eq!(guessed.site(), Site::Sukebei);
+
+    // Mirrors of Sukebei get Sukebei's trackers.
+    let server = common::serve(common::nyaa(1));
+    let mirror = NyaaClient::builder()
+        .site(Site::Sukebei)
+        .base_url(&server.url)
+        .build()
+        .unwrap();
+    let feed = mirror.search_rss(&SearchQuery::new("")).unwrap();
+    assert_eq!(
+        feed[0].magnet().unwrap().trackers,
+        Site::Sukebei.trackers().to_vec()
+    );
+}
@@ -0,0 +1,65 @@
+//! Fixtures and a route that stand in for Nyaa.si in the client tests.
+
+pub const SEARCH_PAGE: &str = include_str!("../fixtures/search.html");
+pub const VIEW_PAGE: &str = include_str!("../fixtures/view.html");
+pub const RSS_FEED: &str = include_str!("../fixtures/rss.xml");
+pub const TORRENT_FILE: &[u8] = include_bytes!("../fixtures/single.torrent");
+
+// The search page with every row removed.
+pub fn empty_search_page() -> String {
+    let start = SEARCH_PAGE.find("<tbody>").unwrap() + "<tbody>".len();
+    let end = SEARCH_PAGE.find("</tbody>").unwrap();
+    format!("{}{}", &SEARCH_PAGE[..start], &SEARCH_PAGE[end..])
+}
+
+// The n-th of `pages` pages of results, with the result count and the
+// pagination widget to match.
+pub fn search_page(n: u32, pages: u32) -> String {
+    let widget = (1..=pages)
+        .map(|p| {
+            if p == n {
+                format!("<li class=\"active\"><a href=\"#\">{} <span class=\"sr-only\">(current)</span></a></li>", p)
+            } else {
+                format!("<li><a href=\"/?p={}\">{}</a></li>", p, p)
+            }
+        })
+        .collect::<String>();
+
+    SEARCH_PAGE
+        .replace(
+            "results 1-2 out of 2",
+            &format!("results {}-{} out of {}", 2 * n - 1, 2 * n, 2 * pages),
+        )
+        .replace(
+            "<li class=\"active\"><a href=\"#\">1 <span class=\"sr-only\">(current)</span></a></li>",
+            &widget,
+        )
+}
+
+pub use nyaadesu::testing::{serve, FakeServer as Server};
+
+// Route that answers like Nyaa.si with `pages` full pages of results.
+pub fn nyaa(pages: u32) -> impl Fn(&str) -> Option<Vec<u8>> + Send + 'static {
+    move |path: &str| {
+        if path.starts_with("/view/1300001") {
+            Some(VIEW_PAGE.into())
+        } else if path == "/download/1300001.torrent" {
+            Some(TORRENT_FILE.to_vec())
+        } else if path.contains("page=rss") {
+            Some(RSS_FEED.into())
+        } else if path.starts_with("/?") {
+            let page = path
+                .split('&')
+                .find_map(|p| p.strip_prefix("p="))
+                .and_then(|p| p.parse::<u32>().ok())
+                .unwrap_or(1);
+            if page <= pages {
+                Some(search_page(page, pages).into_bytes())
+            } else {
+                Some(empty_search_page().into_bytes())
+            }
+        } else {
+            None
+        }
+    }
+}
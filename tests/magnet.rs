@@ -0,0 +1,100 @@
+extern crate nyaadesu;
+
+use nyaadesu::*;
+
+const HEX: &str = "0123456789abcdef0123456789abcdef01234567";
+const BASE32: &str = "AERUKZ4JVPG66AJDIVTYTK6N54ASGRLH";
+
+#[test]
+fn parse_nyaa_magnet() {
+    let magnet: Magnet = format!(
+        "magnet:?xt=urn:btih:{}&dn=Little%20Witch&xl=1503238554\
+         &tr=http%3A%2F%2Fnyaa.tracker.wf%3A7777%2Fannounce&tr=udp%3A%2F%2Fopen.stealth.si%3A80%2Fannounce\
+         &ws=https%3A%2F%2Fexample.com%2Flw&x.pe=1.2.3.4%3A6881",
+        HEX.to_uppercase()
+    )
+    .parse()
+    .unwrap();
+
+    assert_eq!(magnet.info_hash.to_hex(), HEX);
+    assert_eq!(magnet.info_hash.to_base32(), BASE32);
+    assert_eq!(magnet.name.as_deref(), Some("Little Witch"));
+    assert_eq!(magnet.length, Some(1503238554));
+    assert_eq!(
+        magnet.trackers,
+        vec![
+            "http://nyaa.tracker.wf:7777/announce",
+            "udp://open.stealth.si:80/announce"
+        ]
+    );
+    assert_eq!(magnet.web_seeds, vec!["https://example.com/lw"]);
+}
+
+#[test]
+fn hex_and_base32_hashes_are_the_same() {
+    let hex: Magnet = format!("magnet:?xt=urn:btih:{}", HEX).parse().unwrap();
+    let base32: Magnet = format!("magnet:?xt=urn:btih:{}", BASE32.to_lowercase())
+        .parse()
+        .unwrap();
+
+    assert_eq!(hex, base32);
+    assert_eq!(hex.to_string(), format!("magnet:?xt=urn:btih:{}", HEX));
+}
+
+#[test]
+fn serialization_is_canonical() {
+    let uri = format!(
+        "magnet:?tr=udp%3a%2f%2ft%3a1&dn=Little+Witch&xt=urn:btih:{}&tr.1=udp://u:2",
+        BASE32
+    );
+    let magnet = Magnet::parse(&uri).unwrap();
+    let canonical = format!(
+        "magnet:?xt=urn:btih:{}&dn=Little%20Witch&tr=udp%3A%2F%2Ft%3A1&tr=udp%3A%2F%2Fu%3A2",
+        HEX
+    );
+
+    assert_eq!(magnet.to_uri(), canonical);
+    assert_eq!(Magnet::parse(&canonical).unwrap(), magnet);
+}
+
+#[test]
+fn invalid_magnets() {
+    let cases = [
+        (
+            "https://nyaa.si/download/1.torrent",
+            MagnetError::NotAMagnet,
+        ),
+        ("magnet:?dn=nothing", MagnetError::MissingInfoHash),
+        ("magnet:?xt=urn:btih:0123", MagnetError::InvalidInfoHash),
+        (
+            "magnet:?xt=urn:btih:+123456789abcdef0123456789abcdef01234567",
+            MagnetError::InvalidInfoHash,
+        ),
+        (
+            "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&xl=big",
+            MagnetError::InvalidLength,
+        ),
+        (
+            "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&dn=%ff",
+            MagnetError::InvalidEncoding,
+        ),
+    ];
+
+    for (uri, error) in cases.iter() {
+        assert_eq!(Magnet::parse(uri), Err(*error), "{}", uri);
+    }
+}
+
+#[test]
+fn torrents_magnets() {
+    let page = parse_search_page(include_str!("fixtures/search.html")).unwrap();
+    let magnet = page.torrents[0].magnet().unwrap();
+    assert_eq!(magnet.info_hash.to_hex(), HEX);
+    assert_eq!(magnet.name.as_deref(), Some("Little Witch"));
+
+    let feed = parse_rss(include_str!("fixtures/rss.xml")).unwrap();
+    let magnet = feed[0].magnet().unwrap();
+    assert_eq!(magnet.info_hash.to_hex(), HEX);
+    assert_eq!(magnet.trackers.len(), 5);
+    assert_eq!(feed[0].magnet_link(), Some(magnet.to_uri()));
+}
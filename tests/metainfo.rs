@@ -0,0 +1,95 @@
+extern crate nyaadesu;
+
+use nyaadesu::*;
+
+const SINGLE: &[u8] = include_bytes!("fixtures/single.torrent");
+const MULTI: &[u8] = include_bytes!("fixtures/multi.torrent");
+
+#[test]
+fn single_file_torrent() {
+    let metainfo = parse_torrent_file(SINGLE).unwrap();
+
+    assert_eq!(
+        metainfo.info_hash.to_hex(),
+        "3ca2c86efa00c4f4620d26ab07f09de8e7f538f6"
+    );
+    assert_eq!(metainfo.name, "[LWA] Little Witch Academia - 01.mkv");
+    assert_eq!(metainfo.piece_length, 1048576);
+    assert_eq!(metainfo.pieces.len(), 3);
+    assert_eq!(metainfo.pieces[1][0], 20);
+    assert_eq!(metainfo.layout, Layout::SingleFile { length: 1503238554 });
+    assert_eq!(
+        metainfo.announce.as_deref(),
+        Some("http://nyaa.tracker.wf:7777/announce")
+    );
+    assert_eq!(metainfo.announce_list.len(), 2);
+    assert_eq!(metainfo.announce_list[1].len(), 2);
+    assert_eq!(metainfo.creation_date, Some(1600000000));
+    assert_eq!(
+        metainfo.comment.as_deref(),
+        Some("https://nyaa.si/view/1300001")
+    );
+    assert_eq!(metainfo.created_by.as_deref(), Some("NyaaV2"));
+
+    let magnet = metainfo.magnet();
+    assert_eq!(magnet.info_hash, metainfo.info_hash);
+    assert_eq!(magnet.length, Some(1503238554));
+    assert_eq!(magnet.trackers.len(), 3);
+}
+
+#[test]
+fn multi_file_torrent() {
+    let metainfo = parse_torrent_file(MULTI).unwrap();
+
+    assert_eq!(
+        metainfo.info_hash.to_hex(),
+        "f8ccf4756a4c75b22f732ec5cc4f88eba5928518"
+    );
+    assert_eq!(metainfo.name, "[LWA] Little Witch Academia");
+    assert_eq!(
+        metainfo.layout,
+        Layout::MultiFile {
+            files: vec![
+                MetainfoFile {
+                    path: vec!["Season 1".to_string(), "01.mkv".to_string()],
+                    length: 734003200,
+                },
+                MetainfoFile {
+                    path: vec![
+                        "Extras".to_string(),
+                        "Menu".to_string(),
+                        "ncop.txt".to_string()
+                    ],
+                    length: 1024,
+                },
+            ]
+        }
+    );
+    assert_eq!(metainfo.total_length(), 734004224);
+    assert_eq!(metainfo.announce_list, Vec::<Vec<String>>::new());
+    assert_eq!(metainfo.creation_date, None);
+}
+
+#[test]
+fn invalid_torrents() {
+    let cases: [(&[u8], &str); 5] = [
+        (b"", "bencode"),
+        (b"d8:announce3:urle", "info"),
+        (
+            b"d4:infod4:name1:a12:piece lengthi1e6:pieces3:abc6:lengthi1eee",
+            "pieces",
+        ),
+        (b"d4:infod4:name1:a12:piece lengthi1e6:pieces0:ee", "length"),
+        (
+            b"d4:infod4:name1:a12:piece lengthi-1e6:pieces0:6:lengthi1eee",
+            "piece length",
+        ),
+    ];
+
+    for (bytes, field) in cases.iter() {
+        match parse_torrent_file(bytes) {
+            Err(Error::Scraping(e)) => assert_eq!(e.field, *field),
+            other => panic!("expected a scraping error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Scraping must never panic, whatever the pages look like: malformed input
+//! has to end in an `Error`.
+
+extern crate nyaadesu;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+const SEARCH_PAGE: &str = include_str!("fixtures/search.html");
+const VIEW_PAGE: &str = include_str!("fixtures/view.html");
+const RSS_FEED: &str = include_str!("fixtures/rss.xml");
+const TORRENT_FILE: &[u8] = include_bytes!("fixtures/multi.torrent");
+
+// Replace the bytes in [start, start + len) of a page with junk.
+fn mutate(page: &str, start: usize, len: usize, junk: &str) -> String {
+    let bytes = page.as_bytes();
+    let start = start % bytes.len();
+    let end = (start + len).min(bytes.len());
+
+    let mut mutated = bytes[..start].to_vec();
+    mutated.extend_from_slice(junk.as_bytes());
+    mutated.extend_from_slice(&bytes[end..]);
+
+    String::from_utf8_lossy(&mutated).into_owned()
+}
+
+// Strategy that generates html-looking soup made of the tags Nyaa.si uses.
+fn html_soup() -> impl Strategy<Value = String> {
+    "(<(/)?(html|body|div|table|tbody|tr|td|a|span|ul|li|kbd)( (class|href|title|data-timestamp)=\"[a-z0-9_ /:.-]{0,12}\")?>|[a-zA-Z0-9 .:-]{0,12}|\n\t\t){0,80}"
+}
+
+#[test]
+fn fixtures_parse() {
+    assert_eq!(
+        nyaadesu::parse_search_page(SEARCH_PAGE)
+            .unwrap()
+            .torrents
+            .len(),
+        2
+    );
+    assert_eq!(
+        nyaadesu::parse_view_page(VIEW_PAGE).unwrap().torrent.id,
+        1300001
+    );
+    assert_eq!(nyaadesu::parse_rss(RSS_FEED).unwrap().len(), 1);
+}
+
+#[test]
+fn bad_numbers_are_errors() {
+    let pages = [
+        SEARCH_PAGE.replace(
+            "data-timestamp=\"1600000000\"",
+            "data-timestamp=\"yesterday\"",
+        ),
+        SEARCH_PAGE.replace("data-timestamp=\"1600000000\"", "data-timestamp=\"-1\""),
+        SEARCH_PAGE.replace("1.4 GiB", "lots GiB"),
+        SEARCH_PAGE.replace("1.4 GiB", "NaN GiB"),
+        SEARCH_PAGE.replace("1.4 GiB", "1.4 XiB"),
+        SEARCH_PAGE.replace("1.4 GiB", "20000 EiB"),
+        SEARCH_PAGE.replace("1.4 GiB", "1.4"),
+        SEARCH_PAGE.replace(">123<", ">99999999999999999999<"),
+    ];
+
+    for page in pages.iter() {
+        match nyaadesu::parse_search_page(page) {
+            Err(nyaadesu::Error::Scraping(e)) => assert_eq!(e.row, Some(0)),
+            other => panic!("expected a scraping error, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn bad_dates_in_rss_are_errors() {
+    let dates = [
+        "Sun, 13 Sep 99999999999999 12:26:40 -0000",
+        "Sun, 13 Sep 2020 99999999999999:26:40 -0000",
+        "Sun, 13 Sep 2020 12:26:40 -99999999999999",
+        "Sun, 13 Sep 2020 12:26:40:10:10 -0000",
+        "Sun, 13 Sep",
+        "",
+    ];
+
+    for date in dates.iter() {
+        let feed = RSS_FEED.replace("Sun, 13 Sep 2020 12:26:40 -0000", date);
+        assert!(nyaadesu::parse_rss(&feed).is_err(), "{}", date);
+    }
+}
+
+#[test]
+fn deeply_nested_torrent_files_do_not_panic() {
+    let file = format!("d4:info{}", "l".repeat(100_000));
+    assert!(nyaadesu::parse_torrent_file(file.as_bytes()).is_err());
+}
+
+#[test]
+fn truncated_pages_do_not_panic() {
+    for page in [SEARCH_PAGE, VIEW_PAGE, RSS_FEED].iter() {
+        for end in (0..page.len()).step_by(7) {
+            let truncated = &page[..end];
+            let _ = nyaadesu::parse_search_page(truncated);
+            let _ = nyaadesu::parse_view_page(truncated);
+            let _ = nyaadesu::parse_rss(truncated);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_text_does_not_panic(page in any::<String>()) {
+        let _ = nyaadesu::parse_search_page(&page);
+        let _ = nyaadesu::parse_view_page(&page);
+        let _ = nyaadesu::parse_rss(&page);
+    }
+
+    #[test]
+    fn html_soup_does_not_panic(page in html_soup()) {
+        let _ = nyaadesu::parse_search_page(&page);
+        let _ = nyaadesu::parse_view_page(&page);
+    }
+
+    #[test]
+    fn mutated_search_page_does_not_panic(
+        start in any::<usize>(),
+        len in 0usize..64,
+        junk in "[<>/\"= a-z0-9.-]{0,16}",
+    ) {
+        let _ = nyaadesu::parse_search_page(&mutate(SEARCH_PAGE, start, len, &junk));
+    }
+
+    #[test]
+    fn pagination_numbers_do_not_panic(
+        start in any::<u32>(),
+        end in any::<u32>(),
+        total in any::<u32>(),
+        page in any::<u32>(),
+    ) {
+        let page = SEARCH_PAGE
+            .replace("results 1-2 out of 2", &format!("results {}-{} out of {}", start, end, total))
+            .replace("1 <span class=\"sr-only\">", &format!("{} <span class=\"sr-only\">", page));
+        let _ = nyaadesu::parse_search_page(&page);
+    }
+
+    #[test]
+    fn mutated_view_page_does_not_panic(
+        start in any::<usize>(),
+        len in 0usize..64,
+        junk in "[<>/\"= a-z0-9.-]{0,16}",
+    ) {
+        let _ = nyaadesu::parse_view_page(&mutate(VIEW_PAGE, start, len, &junk));
+    }
+
+    #[test]
+    fn scraped_timestamps_do_not_panic(
+        timestamp in prop_oneof![Just(u64::MAX), u64::MAX - 86_400..=u64::MAX, any::<u64>()],
+    ) {
+        let timestamp = format!("data-timestamp=\"{}\"", timestamp);
+        let search = SEARCH_PAGE.replace("data-timestamp=\"1600000000\"", &timestamp);
+        let view = VIEW_PAGE.replace("data-timestamp=\"1600000000\"", &timestamp);
+
+        let dates = nyaadesu::parse_search_page(&search)
+            .unwrap()
+            .torrents
+            .iter()
+            .map(|t| t.date)
+            .chain(Some(nyaadesu::parse_view_page(&view).unwrap().torrent.date))
+            .collect::<Vec<_>>();
+        for date in dates {
+            let _ = date.to_system_time();
+            let _ = date.to_string();
+            let _ = date.to_rfc2822();
+            let _ = date.relative();
+        }
+    }
+
+    #[test]
+    fn arbitrary_torrent_files_do_not_panic(file in any::<Vec<u8>>()) {
+        let _ = nyaadesu::parse_torrent_file(&file);
+    }
+
+    #[test]
+    fn mutated_torrent_files_do_not_panic(
+        start in any::<usize>(),
+        len in 0usize..16,
+        junk in "[dlie0-9:a-z]{0,8}",
+    ) {
+        let mut file = TORRENT_FILE.to_vec();
+        let start = start % file.len();
+        let end = (start + len).min(file.len());
+        file.splice(start..end, junk.bytes());
+
+        let _ = nyaadesu::parse_torrent_file(&file);
+    }
+
+    #[test]
+    fn mutated_rss_feed_does_not_panic(
+        start in any::<usize>(),
+        len in 0usize..64,
+        junk in "[<>/\"= a-z0-9:.-]{0,16}",
+    ) {
+        let _ = nyaadesu::parse_rss(&mutate(RSS_FEED, start, len, &junk));
+    }
+}
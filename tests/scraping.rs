@@ -0,0 +1,314 @@
+extern crate nyaadesu;
+
+use nyaadesu::*;
+
+const SEARCH_PAGE: &str = include_str!("fixtures/search.html");
+
+#[test]
+fn lenient_mode_skips_bad_rows() {
+    let page = SEARCH_PAGE.replace("350.2 MiB", "350.2 XiB");
+
+    let strict = parse_search_page_with(&page, ParseMode::Strict);
+    match strict {
+        Err(Error::Scraping(e)) => {
+            assert_eq!(e.row, Some(1));
+            assert_eq!(e.field, "size");
+            assert_eq!(e.value.as_deref(), Some("350.2 XiB"));
+            assert_eq!(
+                e.to_string(),
+                "could not scrape size of row 1 of a search page (found \"350.2 XiB\")"
+            );
+        }
+        other => panic!("expected a scraping error, got {:?}", other),
+    }
+
+    let lenient = parse_search_page_with(&page, ParseMode::Lenient).unwrap();
+    assert_eq!(lenient.torrents.len(), 1);
+    assert_eq!(lenient.errors.len(), 1);
+    assert_eq!(lenient.errors[0].error.row, Some(1));
+    assert_eq!(lenient.errors[0].error.field, "size");
+    assert!(lenient.errors[0].html.starts_with("<tr class=\"danger\">"));
+    assert!(lenient.errors[0].html.contains("350.2 XiB"));
+}
+
+#[test]
+fn unparsable_values_are_reported() {
+    fn value<T: std::fmt::Debug>(result: Result<T, Error>) -> (&'static str, Option<String>) {
+        match result {
+            Err(Error::Scraping(e)) => (e.field, e.value),
+            other => panic!("expected a scraping error, got {:?}", other),
+        }
+    }
+    let found = |field, value: &str| (field, Some(value.to_string()));
+
+    let page = SEARCH_PAGE.replace(
+        "<a href=\"/?c=1_4\" title=\"Anime - Raw\">",
+        "<a href=\"/?c=1_9\" title=\"Anime - Audio Drama\">",
+    );
+    assert_eq!(
+        value(parse_search_page(&page)),
+        found("category", "Anime - Audio Drama")
+    );
+    let page = SEARCH_PAGE.replace("\"1599990000\"", "\"yesterday\"");
+    assert_eq!(value(parse_search_page(&page)), found("date", "yesterday"));
+    let page = SEARCH_PAGE.replace(" data-timestamp=\"1599990000\"", "");
+    assert_eq!(value(parse_search_page(&page)), ("date", None));
+
+    let view = include_str!("fixtures/view.html").replace(
+        "<div class=\"col-md-5\">1.4 GiB</div>",
+        "<div class=\"col-md-5\">1.4 XiB</div>",
+    );
+    assert_eq!(value(parse_view_page(&view)), found("size", "1.4 XiB"));
+
+    let feed = include_str!("fixtures/rss.xml").replace(
+        "<nyaa:size>1.4 GiB</nyaa:size>",
+        "<nyaa:size>1.4 XiB</nyaa:size>",
+    );
+    assert_eq!(value(parse_rss(&feed)), found("size", "1.4 XiB"));
+}
+
+#[test]
+fn layout_shifts_do_not_break_the_parser() {
+    let expected = parse_search_page(SEARCH_PAGE).unwrap();
+
+    // A notice above the navbar and another above the table.
+    let notices = SEARCH_PAGE
+        .replace(
+            "<!-- Fixed navbar -->",
+            "<div class=\"alert alert-warning\">Maintenance tonight</div>",
+        )
+        .replace(
+            "<div class=\"table-responsive\">",
+            "<div class=\"alert alert-info\">New mirror!</div><div class=\"table-responsive\">",
+        );
+    assert_eq!(parse_search_page(&notices).unwrap(), expected);
+
+    // No indentation at all.
+    let minified = SEARCH_PAGE.lines().map(|l| l.trim()).collect::<String>();
+    assert_eq!(parse_search_page(&minified).unwrap(), expected);
+
+    // The magnet icon before the download one.
+    let download =
+        "<a href=\"/download/1300001.torrent\"><i class=\"fa fa-fw fa-download\"></i></a>";
+    let start = SEARCH_PAGE.find(download).unwrap();
+    let magnet_end = SEARCH_PAGE[start..]
+        .find("</a>\n\t\t\t\t\t\t\t</td>")
+        .unwrap()
+        + start
+        + 4;
+    let magnet = SEARCH_PAGE[start + download.len()..magnet_end].trim();
+    let reordered = format!(
+        "{}{}\n{}{}",
+        &SEARCH_PAGE[..start],
+        magnet,
+        download,
+        &SEARCH_PAGE[magnet_end..]
+    );
+    assert!(reordered.find("fa-magnet").unwrap() < reordered.find("fa-download").unwrap());
+    assert_eq!(parse_search_page(&reordered).unwrap(), expected);
+}
+
+#[test]
+fn links_and_ids() {
+    let page = parse_search_page(SEARCH_PAGE).unwrap();
+    let torrent = &page.torrents[0];
+
+    assert_eq!(torrent.id, 1300001);
+    assert_eq!(
+        torrent.links.download_url.as_deref(),
+        Some("/download/1300001.torrent")
+    );
+    assert_eq!(torrent.links.view_url.as_deref(), Some("/view/1300001"));
+    assert!(torrent
+        .links
+        .magnet
+        .as_ref()
+        .unwrap()
+        .starts_with("magnet:?"));
+    assert_eq!(
+        torrent.torrent_file().as_deref(),
+        Some("https://nyaa.si/download/1300001.torrent")
+    );
+    assert_eq!(
+        torrent.view_page().as_deref(),
+        Some("https://nyaa.si/view/1300001")
+    );
+
+    let details = parse_view_page(include_str!("fixtures/view.html")).unwrap();
+    assert_eq!(details.torrent.id, 1300001);
+    assert_eq!(details.torrent.links, torrent.links);
+
+    let feed = parse_rss(include_str!("fixtures/rss.xml")).unwrap();
+    assert_eq!(feed[0].id, 1300001);
+    assert_eq!(
+        feed[0].view_page().as_deref(),
+        Some("https://nyaa.si/view/1300001")
+    );
+}
+
+#[test]
+fn view_page_info_hash() {
+    const HASH: &str = "0123456789abcdef0123456789abcdef01234567";
+    let view = include_str!("fixtures/view.html");
+
+    let details = parse_view_page(view).unwrap();
+    assert_eq!(details.info_hash.to_hex(), HASH);
+    assert_eq!(
+        Some(details.info_hash),
+        details.torrent.magnet().map(|m| m.info_hash)
+    );
+
+    let upper = view.replace(HASH, &HASH.to_uppercase());
+    assert_eq!(parse_view_page(&upper).unwrap().info_hash.to_hex(), HASH);
+
+    let malformed = view.replace(HASH, "0123456789abcdef");
+    match parse_view_page(&malformed) {
+        Err(Error::Scraping(e)) => assert_eq!(e.field, "info hash"),
+        other => panic!("expected a scraping error, got {:?}", other),
+    }
+}
+
+#[test]
+fn comments_and_status() {
+    let page = parse_search_page(SEARCH_PAGE).unwrap();
+    assert_eq!(page.torrents[0].comments, 3);
+    assert_eq!(page.torrents[0].status, Status::Trusted);
+    assert_eq!(page.torrents[1].comments, 0);
+    assert_eq!(page.torrents[1].status, Status::Remake);
+
+    let normal = SEARCH_PAGE.replace("<tr class=\"success\">", "<tr class=\"default\">");
+    let page = parse_search_page(&normal).unwrap();
+    assert_eq!(page.torrents[0].status, Status::Normal);
+
+    let details = parse_view_page(include_str!("fixtures/view.html")).unwrap();
+    assert_eq!(details.torrent.comments, 1);
+    assert_eq!(details.torrent.status, Status::Trusted);
+
+    let feed = parse_rss(include_str!("fixtures/rss.xml")).unwrap();
+    assert_eq!(feed[0].comments, 3);
+    assert_eq!(feed[0].status, Status::Trusted);
+}
+
+#[test]
+fn sukebei_pages() {
+    let page = SEARCH_PAGE
+        .replace("Anime - English-translated", "Art - Doujinshi")
+        .replace("Anime - Raw", "Real Life - Videos")
+        .replace("/?c=1_4", "/?c=2_2");
+    let page = parse_search_page(&page).unwrap();
+    assert_eq!(page.torrents[0].category, Category::Art(Art::Doujinshi));
+    assert_eq!(
+        page.torrents[1].category,
+        Category::RealLife(RealLife::Videos)
+    );
+
+    let view = include_str!("fixtures/view.html").replace(
+        "<a href=\"/?c=1_0\">Anime</a> - <a href=\"/?c=1_2\">English-translated</a>",
+        "<a href=\"/?c=1_0\">Art</a> - <a href=\"/?c=1_2\">Doujinshi</a>",
+    );
+    let details = parse_view_page(&view).unwrap();
+    assert_eq!(details.torrent.category, Category::Art(Art::Doujinshi));
+
+    let feed = include_str!("fixtures/rss.xml")
+        .replace(
+            "<nyaa:category>Anime - English-translated</nyaa:category>",
+            "<nyaa:category>Art - Doujinshi</nyaa:category>",
+        )
+        .replace("https://nyaa.si/", "https://sukebei.nyaa.si/");
+    let feed = parse_rss_from(&feed, Site::Sukebei).unwrap();
+    assert_eq!(feed[0].category, Category::Art(Art::Doujinshi));
+    assert_eq!(
+        feed[0].magnet().unwrap().trackers,
+        Site::Sukebei.trackers().to_vec()
+    );
+    assert_eq!(
+        feed[0].magnet().unwrap().trackers[0],
+        "http://sukebei.tracker.wf:8888/announce"
+    );
+    assert_eq!(
+        feed[0].view_page().as_deref(),
+        Some("https://sukebei.nyaa.si/view/1300001")
+    );
+    assert_eq!(
+        feed[0].torrent_file().as_deref(),
+        Some("https://sukebei.nyaa.si/download/1300001.torrent")
+    );
+
+    // Without a category name, the code is read with the site's codes.
+    let feed = include_str!("fixtures/rss.xml").replace(
+        "<nyaa:category>Anime - English-translated</nyaa:category>",
+        "",
+    );
+    let feed = parse_rss_from(&feed, Site::Sukebei).unwrap();
+    assert_eq!(feed[0].category, Category::Art(Art::Doujinshi));
+
+    // Relative links are resolved against the site of the category.
+    let torrent = &page.torrents[0];
+    assert_eq!(
+        torrent.view_page().as_deref(),
+        Some("https://sukebei.nyaa.si/view/1300001")
+    );
+    assert_eq!(
+        torrent.torrent_file().as_deref(),
+        Some("https://sukebei.nyaa.si/download/1300001.torrent")
+    );
+
+    // Without a category name, the code is read with the site's codes.
+    let feed = include_str!("fixtures/rss.xml").replace(
+        "<nyaa:category>Anime - English-translated</nyaa:category>",
+        "",
+    );
+    let feed = parse_rss_from(&feed, Site::Sukebei).unwrap();
+    assert_eq!(feed[0].category, Category::Art(Art::Doujinshi));
+    assert_eq!(
+        parse_rss(include_str!("fixtures/rss.xml")).unwrap()[0]
+            .magnet()
+            .unwrap()
+            .trackers[0],
+        "http://nyaa.tracker.wf:7777/announce"
+    );
+}
+
+#[test]
+fn search_meta() {
+    let page = parse_search_page(SEARCH_PAGE).unwrap();
+    assert_eq!(
+        page.meta,
+        Some(SearchMeta {
+            total_results: 2,
+            page_size: 2,
+            current_page: 1,
+            last_page: 1,
+        })
+    );
+    assert!(page.meta.unwrap().is_last_page());
+
+    // Page 3 of a search with more results than Nyaa.si shows: the widget
+    // only links a few pages around the current one.
+    let widget = (1..=5)
+        .map(|p| match p {
+            3 => "<li class=\"active\"><a href=\"#\">3 <span class=\"sr-only\">(current)</span></a></li>".to_string(),
+            p => format!("<li><a href=\"/?p={}\">{}</a></li>", p, p),
+        })
+        .collect::<String>();
+    let html = SEARCH_PAGE
+        .replace("results 1-2 out of 2", "results 151-225 out of 1000")
+        .replace(
+            "<li class=\"active\"><a href=\"#\">1 <span class=\"sr-only\">(current)</span></a></li>",
+            &widget,
+        );
+    let meta = parse_search_page(&html).unwrap().meta.unwrap();
+    assert_eq!(
+        meta,
+        SearchMeta {
+            total_results: 1000,
+            page_size: 75,
+            current_page: 3,
+            last_page: 14,
+        }
+    );
+    assert!(!meta.is_last_page());
+
+    let html = SEARCH_PAGE.replace("pagination-page-info", "");
+    assert_eq!(parse_search_page(&html).unwrap().meta, None);
+}
@@ -0,0 +1,144 @@
+extern crate nyaadesu;
+extern crate serde_json;
+
+use nyaadesu::*;
+use serde_json::json;
+
+const SEARCH_PAGE: &str = include_str!("fixtures/search.html");
+
+#[test]
+fn torrent_json_shape() {
+    let page = parse_search_page(SEARCH_PAGE).unwrap();
+    let value = serde_json::to_value(&page.torrents[0]).unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "id": 1300001,
+            "category": "1_2",
+            "name": "[SubsPlease] Little Witch Academia - 01 (1080p)",
+            "links": {
+                "download_url": "/download/1300001.torrent",
+                "magnet": "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&dn=Little%20Witch&tr=http%3A%2F%2Fnyaa.tracker.wf%3A7777%2Fannounce",
+                "view_url": "/view/1300001",
+            },
+            "size": 1503238554u64,
+            "date": 1600000000u64,
+            "seeders": 123,
+            "leechers": 4,
+            "completed_downloads": 5678,
+            "comments": 3,
+            "status": "trusted",
+        })
+    );
+}
+
+#[test]
+fn round_trips() {
+    let page = parse_search_page(SEARCH_PAGE).unwrap();
+    let json = serde_json::to_string(&page.torrents).unwrap();
+    let torrents: Vec<Torrent> = serde_json::from_str(&json).unwrap();
+    assert_eq!(torrents, page.torrents);
+
+    let details = parse_view_page(include_str!("fixtures/view.html")).unwrap();
+    let json = serde_json::to_string(&details).unwrap();
+    assert_eq!(
+        serde_json::from_str::<TorrentDetails>(&json).unwrap(),
+        details
+    );
+
+    let metainfo = parse_torrent_file(include_bytes!("fixtures/multi.torrent")).unwrap();
+    let json = serde_json::to_string(&metainfo).unwrap();
+    assert_eq!(serde_json::from_str::<Metainfo>(&json).unwrap(), metainfo);
+
+    let query = SearchQuery::new("witch")
+        .category(CategoryFilter::Anime)
+        .sort(Sort::Seeders)
+        .order(Order::Ascending);
+    let json = serde_json::to_string(&query).unwrap();
+    assert_eq!(serde_json::from_str::<SearchQuery>(&json).unwrap(), query);
+}
+
+#[test]
+fn categories_are_codes() {
+    for category in Category::all() {
+        let value = serde_json::to_value(category).unwrap();
+        let expected = match category.site() {
+            Site::Nyaa => category.code().to_string(),
+            Site::Sukebei => format!("sukebei:{}", category.code()),
+        };
+        assert_eq!(value, json!(expected));
+        assert_eq!(serde_json::from_value::<Category>(value).unwrap(), category);
+    }
+
+    assert_eq!(
+        serde_json::to_value(CategoryFilter::Anime).unwrap(),
+        json!("1_0")
+    );
+    assert_eq!(
+        serde_json::to_value(CategoryFilter::Art).unwrap(),
+        json!("sukebei:1_0")
+    );
+    assert_eq!(
+        serde_json::from_value::<CategoryFilter>(json!("sukebei:1_4")).unwrap(),
+        CategoryFilter::Only(Category::Art(Art::Manga))
+    );
+    assert_eq!(serde_json::to_value(Anime::Raw).unwrap(), json!("1_4"));
+
+    assert!(serde_json::from_value::<Category>(json!("9_9")).is_err());
+    assert!(serde_json::from_value::<Category>(json!("Anime - Raw")).is_err());
+}
+
+#[test]
+fn enums_and_hashes() {
+    assert_eq!(
+        serde_json::to_value(Site::Sukebei).unwrap(),
+        json!("sukebei")
+    );
+    assert_eq!(
+        serde_json::to_value(Status::Remake).unwrap(),
+        json!("remake")
+    );
+    assert_eq!(
+        serde_json::to_value(Filter::TrustedOnly).unwrap(),
+        json!("trusted_only")
+    );
+
+    let hash: InfoHash = "3ca2c86efa00c4f4620d26ab07f09de8e7f538f6".parse().unwrap();
+    assert_eq!(
+        serde_json::to_value(hash).unwrap(),
+        json!("3ca2c86efa00c4f4620d26ab07f09de8e7f538f6")
+    );
+    let magnet = Magnet::new(hash);
+    assert_eq!(
+        serde_json::to_value(&magnet).unwrap(),
+        json!("magnet:?xt=urn:btih:3ca2c86efa00c4f4620d26ab07f09de8e7f538f6")
+    );
+
+    let file = FileEntry::File {
+        name: "a.mkv".to_string(),
+        size: ByteSize(1024),
+    };
+    assert_eq!(
+        serde_json::to_value(&file).unwrap(),
+        json!({"type": "file", "name": "a.mkv", "size": 1024})
+    );
+}
+
+#[test]
+fn errors_are_kind_and_message() {
+    let error = parse_search_page(&SEARCH_PAGE.replace("350.2 MiB", "350.2 XiB")).unwrap_err();
+    let value = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(value["kind"], json!("scraping"));
+    assert_eq!(value["message"], json!(error.to_string()));
+    assert_eq!(
+        serde_json::to_value(Error::Status(404)).unwrap(),
+        json!({"kind": "status", "message": "server answered with status 404", "status": 404})
+    );
+    assert_eq!(
+        serde_json::to_value(Error::Status(429)).unwrap()["status"],
+        json!(429)
+    );
+    assert!(value.get("status").is_none());
+}
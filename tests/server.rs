@@ -0,0 +1,167 @@
+extern crate nyaadesu;
+extern crate reqwest;
+
+mod common;
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use nyaadesu::testing::{self, FakeNyaa};
+use nyaadesu::*;
+
+// The Torznab server running in front of a stand-in Nyaa.si. It is killed
+// when dropped.
+struct Torznab {
+    child: Child,
+    url: String,
+}
+
+impl Torznab {
+    fn start(nyaa: &common::Server, args: &[&str]) -> Torznab {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_nyaadesu-server"))
+            .args(["--listen", "127.0.0.1:0", "--base-url", &nyaa.url])
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let url = line.trim().trim_start_matches("Listening on ").to_string();
+
+        Torznab { child, url }
+    }
+
+    fn get(&self, path: &str) -> (u16, String) {
+        let response = reqwest::blocking::get(format!("{}{}", self.url, path)).unwrap();
+        (response.status().as_u16(), response.text().unwrap())
+    }
+}
+
+impl Drop for Torznab {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn caps() {
+    let nyaa = common::serve(common::nyaa(1));
+    let torznab = Torznab::start(&nyaa, &[]);
+
+    let (status, caps) = torznab.get("/api?t=caps");
+    assert_eq!(status, 200);
+    assert!(caps.contains("<tv-search available=\"yes\" supportedParams=\"q,season,ep\""));
+    assert!(caps.contains("<subcat id=\"5070\" name=\"TV/Anime\""));
+    assert!(!caps.contains("XXX"));
+    assert_eq!(nyaa.requests(), 0);
+}
+
+#[test]
+fn search_renders_torznab_items() {
+    let nyaa = common::serve(common::nyaa(1));
+    let torznab = Torznab::start(&nyaa, &[]);
+
+    let (status, feed) = torznab.get("/api?t=search&q=little+witch&cat=5070");
+    assert_eq!(status, 200);
+    assert!(feed.contains("xmlns:torznab=\"http://torznab.com/schemas/2015/feed\""));
+    assert_eq!(feed.matches("<item>").count(), 2);
+    assert!(feed.contains("<title>[SubsPlease] Little Witch Academia - 01 (1080p)</title>"));
+    assert!(feed.contains("<size>1503238554</size>"));
+    assert!(feed.contains("<pubDate>Sun, 13 Sep 2020 12:26:40 +0000</pubDate>"));
+    assert!(feed.contains(&format!(
+        "<enclosure url=\"{}/download/1300001.torrent\" length=\"1503238554\"",
+        nyaa.url
+    )));
+    assert!(feed.contains("<torznab:attr name=\"seeders\" value=\"123\""));
+    assert!(feed.contains("<torznab:attr name=\"peers\" value=\"127\""));
+    assert!(feed.contains(
+        "<torznab:attr name=\"infohash\" value=\"0123456789abcdef0123456789abcdef01234567\""
+    ));
+    assert!(feed.contains("<torznab:attr name=\"magneturl\" value=\"magnet:?xt=urn:btih:"));
+
+    // Limits and offsets apply to the results.
+    let (_, feed) = torznab.get("/api?t=tvsearch&q=little+witch&ep=1&limit=1&offset=1");
+    assert_eq!(feed.matches("<item>").count(), 1);
+    assert!(!feed.contains("Little Witch Academia - 01"));
+}
+
+#[test]
+fn categories_without_torrents_are_not_searched() {
+    let nyaa = common::serve(common::nyaa(1));
+    let torznab = Torznab::start(&nyaa, &[]);
+
+    let (_, feed) = torznab.get("/api?t=search&q=witch&cat=6000");
+    assert_eq!(feed.matches("<item>").count(), 0);
+    assert_eq!(nyaa.requests(), 0);
+
+    // Audio is asked for but the stand-in only has anime.
+    let (_, feed) = torznab.get("/api?t=search&q=witch&cat=3000");
+    assert_eq!(feed.matches("<item>").count(), 0);
+    assert_eq!(nyaa.requests(), 1);
+}
+
+#[test]
+fn results_are_filtered_before_the_limit() {
+    // Anime and lossy audio can't be searched for together: every category
+    // is searched and the literature in between is left out.
+    let mut torrents = (1..=8)
+        .map(|id| {
+            let mut torrent = testing::torrent(id, &format!("Torrent {}", id));
+            torrent.category = Category::Literature(Literature::EnglishTranslated);
+            torrent
+        })
+        .collect::<Vec<_>>();
+    torrents[0].category = Category::Anime(Anime::Raw);
+    torrents[4].category = Category::Anime(Anime::Raw);
+    torrents[7].category = Category::Audio(Audio::Lossy);
+    torrents[7].seeders = u32::MAX;
+    torrents[7].leechers = 1;
+    let nyaa = FakeNyaa::new(torrents).page_size(2).start();
+    let torznab = Torznab::start(&nyaa, &[]);
+
+    let titles = |feed: &str| {
+        feed.split("<title>")
+            .skip(2)
+            .map(|t| t.split('<').next().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    let (_, feed) = torznab.get("/api?t=search&cat=5070,3010&limit=3");
+    assert_eq!(titles(&feed), vec!["Torrent 8", "Torrent 5", "Torrent 1"]);
+    let (_, feed) = torznab.get("/api?t=search&cat=5070,3010&limit=1&offset=1");
+    assert_eq!(titles(&feed), vec!["Torrent 5"]);
+
+    // Counts too large to add up are capped.
+    let (_, feed) = torznab.get("/api?t=search&cat=3010");
+    assert!(feed.contains("<torznab:attr name=\"peers\" value=\"4294967295\""));
+
+    let (status, feed) = torznab.get(&format!(
+        "/api?t=search&cat=5070&offset={}&limit=300",
+        usize::MAX
+    ));
+    assert_eq!(status, 200);
+    assert_eq!(feed.matches("<item>").count(), 0);
+}
+
+#[test]
+fn errors() {
+    let nyaa = common::serve(common::nyaa(1));
+    let torznab = Torznab::start(&nyaa, &["--api-key", "secret"]);
+
+    let (status, error) = torznab.get("/api?t=caps");
+    assert_eq!(status, 200);
+    assert!(error.contains("<error code=\"100\""));
+
+    let (_, error) = torznab.get("/api?t=music&apikey=secret");
+    assert!(error.contains("<error code=\"202\""));
+
+    let (_, error) = torznab.get("/api?t=search&limit=lots&apikey=secret");
+    assert!(error.contains("<error code=\"201\""));
+
+    let (status, _) = torznab.get("/rss");
+    assert_eq!(status, 404);
+    assert_eq!(nyaa.requests(), 0);
+}
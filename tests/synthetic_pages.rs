@@ -0,0 +1,160 @@
+//! Regression tests against synthetic pages: written by hand in the shape
+//! Nyaa.si serves them (full head, navbar, sortable table header...) for the
+//! cases the parser has to tell apart. They are not captures of the real site
+//! (their links, hashes and names are made up), so the checks of each case
+//! also run on the pages saved from the live site under
+//! tests/fixtures/captured (see examples/capture_pages.rs).
+
+extern crate nyaadesu;
+
+use nyaadesu::*;
+
+const EMPTY_SEARCH: &str = include_str!("fixtures/synthetic/empty_search.html");
+const LAST_PAGE: &str = include_str!("fixtures/synthetic/last_page.html");
+const REMAKES: &str = include_str!("fixtures/synthetic/remakes.html");
+const TRUSTED: &str = include_str!("fixtures/synthetic/trusted.html");
+const MISSING_MAGNET: &str = include_str!("fixtures/synthetic/missing_magnet.html");
+
+// Checks of a case that hold for any page of that case, captured or not.
+type Check = fn(&SearchPage);
+
+const CASES: [(&str, Check); 5] = [
+    ("empty_search", check_empty_search),
+    ("last_page", check_last_page),
+    ("remakes", check_remakes),
+    ("trusted", check_trusted),
+    ("missing_magnet", check_missing_magnet),
+];
+
+fn check_empty_search(page: &SearchPage) {
+    assert_eq!(page.state, Page::NoTorrents);
+    assert!(page.torrents.is_empty());
+    assert_eq!(page.meta, None);
+}
+
+fn check_last_page(page: &SearchPage) {
+    assert_eq!(page.state, Page::Torrents);
+    let meta = page.meta.unwrap();
+    assert!(meta.is_last_page());
+    assert_eq!(
+        (meta.current_page as usize - 1) * meta.page_size as usize + page.torrents.len(),
+        meta.total_results as usize
+    );
+}
+
+fn check_remakes(page: &SearchPage) {
+    assert!(page.torrents.iter().any(|t| t.status == Status::Remake));
+}
+
+fn check_trusted(page: &SearchPage) {
+    assert!(!page.torrents.is_empty());
+    assert!(page.torrents.iter().all(|t| t.status == Status::Trusted));
+}
+
+fn check_missing_magnet(page: &SearchPage) {
+    let torrent = page
+        .torrents
+        .iter()
+        .find(|t| t.links.magnet.is_none())
+        .unwrap();
+    assert_eq!(torrent.magnet(), None);
+    assert!(torrent.links.download_url.is_some());
+}
+
+// Run the checks of each case on its captured page, if it was captured.
+#[test]
+fn captured_pages() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/captured");
+    for (case, check) in CASES.iter() {
+        let path = dir.join(format!("{}.html", case));
+        if let Ok(html) = std::fs::read_to_string(&path) {
+            check(&parse_search_page(&html).unwrap());
+        }
+    }
+}
+
+#[test]
+fn empty_search() {
+    check_empty_search(&parse_search_page(EMPTY_SEARCH).unwrap());
+}
+
+#[test]
+fn last_page() {
+    let page = parse_search_page(LAST_PAGE).unwrap();
+
+    check_last_page(&page);
+    assert_eq!(
+        page.meta,
+        Some(SearchMeta {
+            total_results: 152,
+            page_size: 75,
+            current_page: 3,
+            last_page: 3,
+        })
+    );
+
+    let ids = page.torrents.iter().map(|t| t.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![1000151, 1000150]);
+    assert_eq!(
+        page.torrents[1].category,
+        Category::Literature(Literature::EnglishTranslated)
+    );
+    assert_eq!(page.torrents[0].comments, 2);
+    assert_eq!(page.torrents[1].comments, 0);
+}
+
+#[test]
+fn remakes() {
+    let page = parse_search_page(REMAKES).unwrap();
+    let torrent = &page.torrents[0];
+
+    check_remakes(&page);
+
+    assert_eq!(torrent.status, Status::Remake);
+    assert_eq!(
+        torrent.name,
+        "[NoobSubs] The Witch & the Beast - 01 (720p) (re-upload)"
+    );
+    assert_eq!(torrent.size, ByteSize::parse("705.1 MiB").unwrap());
+    assert_eq!(torrent.date, Timestamp::from_unix(1705150000));
+    assert_eq!(
+        (
+            torrent.seeders,
+            torrent.leechers,
+            torrent.completed_downloads
+        ),
+        (7, 1, 118)
+    );
+    assert_eq!(page.torrents[1].status, Status::Normal);
+    assert_eq!(page.torrents[1].category, Category::Anime(Anime::Raw));
+}
+
+#[test]
+fn trusted() {
+    let page = parse_search_page(TRUSTED).unwrap();
+
+    check_trusted(&page);
+    assert_eq!(page.torrents[0].comments, 12);
+    assert_eq!(page.torrents[1].category, Category::Audio(Audio::Lossy));
+    assert_eq!(
+        page.torrents[0].magnet().unwrap().info_hash.to_hex(),
+        "a1b2c3d4e5f60718293a4b5c6d7e8f9001122334"
+    );
+}
+
+#[test]
+fn missing_magnet() {
+    let page = parse_search_page(MISSING_MAGNET).unwrap();
+    let torrent = &page.torrents[0];
+
+    check_missing_magnet(&page);
+
+    assert_eq!(torrent.id, 900001);
+    assert_eq!(torrent.links.magnet, None);
+    assert_eq!(torrent.magnet(), None);
+    assert_eq!(
+        torrent.links.download_url.as_deref(),
+        Some("/download/900001.torrent")
+    );
+    assert!(page.torrents[1].links.magnet.is_some());
+}
@@ -0,0 +1,178 @@
+extern crate nyaadesu;
+extern crate proptest;
+
+use nyaadesu::testing::{self, *};
+use nyaadesu::*;
+use proptest::prelude::*;
+
+// The torrent with its size rounded the way Nyaa.si shows it.
+fn as_shown(mut torrent: Torrent) -> Torrent {
+    torrent.size = ByteSize::parse(&torrent.size.to_string()).unwrap();
+    torrent
+}
+
+fn arbitrary_torrent() -> impl Strategy<Value = Torrent> {
+    (
+        (
+            any::<u32>(),
+            0..Category::all().count(),
+            "[a-zA-Z0-9&<>\"'\\[\\]()_.-][a-zA-Z0-9 &<>\"'\\[\\]()_.-]{0,40}[a-zA-Z0-9&<>\"'\\[\\]()_.-]",
+            any::<bool>(),
+            0u64..1 << 60,
+            0u64..253_402_300_799,
+        ),
+        (
+            any::<u32>(),
+            any::<u32>(),
+            any::<u32>(),
+            any::<u32>(),
+            0..3usize,
+        ),
+    )
+        .prop_map(
+            |(
+                (id, category, name, magnet, size, date),
+                (seeders, leechers, completed_downloads, comments, status),
+            )| {
+                let mut torrent = testing::torrent(id, &name);
+                torrent.category = Category::all().nth(category).unwrap();
+                if !magnet {
+                    torrent.links.magnet = None;
+                }
+                torrent.size = ByteSize(size);
+                torrent.date = Timestamp::from_unix(date);
+                torrent.seeders = seeders;
+                torrent.leechers = leechers;
+                torrent.completed_downloads = completed_downloads;
+                torrent.comments = comments;
+                torrent.status = [Status::Normal, Status::Trusted, Status::Remake][status];
+                torrent
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn search_pages_round_trip(torrents in prop::collection::vec(arbitrary_torrent(), 1..5)) {
+        let meta = SearchMeta {
+            total_results: 80,
+            page_size: 75,
+            current_page: 2,
+            last_page: 2,
+        };
+        let page = parse_search_page(&render_search_page(&torrents, Some(meta))).unwrap();
+
+        prop_assert_eq!(page.state, Page::Torrents);
+        prop_assert_eq!(page.meta, Some(meta));
+        prop_assert_eq!(
+            page.torrents,
+            torrents.into_iter().map(as_shown).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rss_feeds_round_trip(torrents in prop::collection::vec(arbitrary_torrent(), 0..5)) {
+        let feed = parse_rss(&render_rss(&torrents, "https://nyaa.si")).unwrap();
+
+        prop_assert_eq!(feed.len(), torrents.len());
+        for (parsed, torrent) in feed.iter().zip(torrents) {
+            prop_assert_eq!(
+                parsed.links.view_url.clone(),
+                Some(format!("https://nyaa.si/view/{}", torrent.id))
+            );
+            if let Some(magnet) = torrent.magnet() {
+                prop_assert_eq!(parsed.magnet().unwrap().info_hash, magnet.info_hash);
+            }
+
+            let mut parsed = parsed.clone();
+            parsed.links = torrent.links.clone();
+            prop_assert_eq!(parsed, as_shown(torrent));
+        }
+    }
+}
+
+#[test]
+fn empty_search_pages() {
+    let page = parse_search_page(&render_search_page(&[], None)).unwrap();
+    assert_eq!(page.state, Page::NoTorrents);
+
+    let meta = SearchMeta {
+        total_results: 150,
+        page_size: 75,
+        current_page: 3,
+        last_page: 2,
+    };
+    let page = parse_search_page(&render_search_page(&[], Some(meta))).unwrap();
+    assert_eq!(page.state, Page::NoMoreTorrents);
+}
+
+#[test]
+fn view_pages_round_trip() {
+    let mut torrent = testing::torrent(1300001, "Little Witch <Academia> & friends");
+    torrent.status = Status::Trusted;
+    torrent.comments = 2;
+    let details = TorrentDetails {
+        torrent: as_shown(torrent),
+        submitter: Some("akko".to_string()),
+        information: Some("https://example.com/?a=1&b=2".to_string()),
+        info_hash: "0123456789abcdef0123456789abcdef01234567".parse().unwrap(),
+        description: "**Little Witch Academia** episode 1.\n\nEnjoy & seed!".to_string(),
+        files: vec![FileEntry::Folder {
+            name: "Little Witch Academia".to_string(),
+            entries: vec![
+                FileEntry::File {
+                    name: "01 <1080p>.mkv".to_string(),
+                    size: ByteSize::parse("1.4 GiB").unwrap(),
+                },
+                FileEntry::Folder {
+                    name: "Extras".to_string(),
+                    entries: vec![FileEntry::File {
+                        name: "readme.txt".to_string(),
+                        size: ByteSize(512),
+                    }],
+                },
+            ],
+        }],
+        comments: vec![
+            Comment {
+                author: "akko".to_string(),
+                date: Timestamp::from_unix(1600000100),
+                text: "Believing heart is your magic!".to_string(),
+            },
+            Comment {
+                author: "diana".to_string(),
+                date: Timestamp::from_unix(1600000200),
+                text: "<3 & thanks".to_string(),
+            },
+        ],
+    };
+
+    let parsed = parse_view_page(&render_view_page(&details)).unwrap();
+    assert_eq!(parsed, details);
+
+    // Anonymous remakes without information.
+    let mut anonymous = details.clone();
+    anonymous.submitter = None;
+    anonymous.information = None;
+    anonymous.torrent.status = Status::Remake;
+    anonymous.torrent.links.magnet = None;
+    anonymous.torrent.comments = 0;
+    anonymous.comments = vec![];
+    anonymous.files = vec![];
+
+    let parsed = parse_view_page(&render_view_page(&anonymous)).unwrap();
+    assert_eq!(parsed, anonymous);
+}
+
+#[test]
+fn torrent_files_can_be_decoded() {
+    let torrent = testing::torrent(1300001, "Little Witch Academia - 01 (1080p).mkv");
+    let metainfo = parse_torrent_file(&render_torrent_file(&torrent)).unwrap();
+
+    assert_eq!(metainfo.name, torrent.name);
+    assert_eq!(metainfo.total_length(), torrent.size.bytes());
+    assert_eq!(
+        metainfo.pieces.len() as u64,
+        torrent.size.bytes().div_ceil(metainfo.piece_length)
+    );
+}
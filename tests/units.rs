@@ -0,0 +1,94 @@
+extern crate nyaadesu;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use nyaadesu::*;
+
+#[test]
+fn sizes_are_parsed_exactly() {
+    let cases = [
+        ("0 Bytes", 0),
+        ("512 Bytes", 512),
+        ("12 B", 12),
+        ("1.0 KiB", 1024),
+        ("350.2 MiB", 367_211_315),
+        ("1.4 GiB", 1_503_238_554),
+        ("2.5 TiB", 2_748_779_069_440),
+        ("1.1 PiB", 1_238_489_897_526_886),
+        ("15.9 EiB", 18_331_451_923_248_866_918),
+    ];
+
+    for (text, bytes) in cases.iter() {
+        assert_eq!(ByteSize::parse(text), Some(ByteSize(*bytes)), "{}", text);
+    }
+
+    for text in [
+        "", "1.4", "1.4 XiB", "-1 KiB", "1e3 KiB", ".5 KiB", "16 EiB", "NaN GiB",
+    ]
+    .iter()
+    {
+        assert_eq!(ByteSize::parse(text), None, "{}", text);
+    }
+}
+
+#[test]
+fn sizes_are_displayed_like_nyaa() {
+    let cases = [
+        (0, "0 Bytes"),
+        (1023, "1023 Bytes"),
+        (1024, "1.0 KiB"),
+        (367_211_315, "350.2 MiB"),
+        (1_503_238_554, "1.4 GiB"),
+        (1_238_489_897_526_886, "1.1 PiB"),
+    ];
+
+    for (bytes, text) in cases.iter() {
+        assert_eq!(ByteSize(*bytes).to_string(), *text);
+    }
+}
+
+#[test]
+fn timestamps() {
+    let date = Timestamp::from_unix(1_600_000_000);
+
+    assert_eq!(date.to_iso8601(), "2020-09-13T12:26:40Z");
+    assert_eq!(date.to_rfc2822(), "Sun, 13 Sep 2020 12:26:40 +0000");
+    assert_eq!(
+        Timestamp::from_unix(0).to_rfc2822(),
+        "Thu, 01 Jan 1970 00:00:00 +0000"
+    );
+    assert_eq!(Timestamp::from_unix(0).to_string(), "1970-01-01T00:00:00Z");
+    assert_eq!(
+        Timestamp::from_unix(951_782_400).to_string(),
+        "2000-02-29T00:00:00Z"
+    );
+    assert_eq!(
+        date.to_system_time(),
+        Some(UNIX_EPOCH + Duration::from_secs(1_600_000_000))
+    );
+    assert_eq!(Timestamp::from(date.to_system_time().unwrap()), date);
+    assert_eq!(Timestamp::from_unix(u64::MAX).to_system_time(), None);
+
+    let later = |seconds: u64| Timestamp::from_unix(1_600_000_000 + seconds);
+    assert_eq!(date.relative_to(later(30)), "just now");
+    assert_eq!(date.relative_to(later(60)), "1 minute ago");
+    assert_eq!(date.relative_to(later(3 * 3600 + 5)), "3 hours ago");
+    assert_eq!(date.relative_to(later(2 * 86400)), "2 days ago");
+    assert_eq!(date.relative_to(later(100 * 86400)), "3 months ago");
+    assert_eq!(date.relative_to(later(800 * 86400)), "2 years ago");
+    assert_eq!(later(86400).relative_to(date), "in 1 day");
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono_conversion() {
+    let date = Timestamp::from_unix(1_600_000_000).to_chrono().unwrap();
+    assert_eq!(date.timestamp(), 1_600_000_000);
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn time_conversion() {
+    let date = Timestamp::from_unix(1_600_000_000).to_time().unwrap();
+    assert_eq!(date.unix_timestamp(), 1_600_000_000);
+}